@@ -0,0 +1,369 @@
+//! Repeated timed runs of the days, with JSON baselines to catch performance regressions.
+use std::fmt::Display;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::format::{format_duration, nanos};
+use crate::input::InputSource;
+use crate::runner::{Day, Part};
+use crate::table::write_table;
+use crate::vm::compiled::{Compiled, FastMachine};
+use crate::vm::Instr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    #[serde(with = "nanos")]
+    pub min: Duration,
+    #[serde(with = "nanos")]
+    pub median: Duration,
+    #[serde(with = "nanos")]
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn new(mut samples: Vec<Duration>) -> Option<Self> {
+        samples.sort_unstable();
+        let mid = samples.len() / 2;
+        let median = if samples.len().is_multiple_of(2) {
+            (*samples.get(mid.checked_sub(1)?)? + samples[mid]) / 2
+        } else {
+            samples[mid]
+        };
+        Some(Self {
+            min: *samples.first()?,
+            median,
+            max: *samples.last()?,
+        })
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} / {} / {}",
+            format_duration(self.min),
+            format_duration(self.median),
+            format_duration(self.max)
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Measurement {
+    pub day: usize,
+    pub part: Part,
+    pub runs: usize,
+    #[serde(rename = "parse_ns")]
+    pub parse: Stats,
+    #[serde(rename = "solve_ns")]
+    pub solve: Stats,
+}
+
+impl Measurement {
+    pub fn median(&self) -> Duration {
+        self.parse.median + self.solve.median
+    }
+}
+
+/// Runs a part `runs` times, parsing the input from scratch every time.
+pub fn measure(day: &Day, raw_input: &str, part: Part, runs: usize) -> Result<Measurement> {
+    let mut parse_times = Vec::with_capacity(runs);
+    let mut solve_times = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let run = day.timed_run(raw_input, part)?;
+        parse_times.push(run.parse_time);
+        solve_times.push(run.solve_time);
+    }
+    let no_runs = || Error::InvalidInput("there must be at least one run".to_owned());
+    Ok(Measurement {
+        day: day.number,
+        part,
+        runs,
+        parse: Stats::new(parse_times).ok_or_else(no_runs)?,
+        solve: Stats::new(solve_times).ok_or_else(no_runs)?,
+    })
+}
+
+/// How long a program takes on `FastMachine` as decoded and with the optimizer's loops,
+/// decoding included.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Optimization {
+    /// Steps of the decoded program.
+    pub steps: usize,
+    pub decoded: Stats,
+    pub optimized: Stats,
+}
+
+impl Optimization {
+    /// How many times faster the optimized program is, by the medians.
+    pub fn speedup(&self) -> f64 {
+        self.decoded.median.as_secs_f64() / self.optimized.median.as_secs_f64().max(1e-9)
+    }
+}
+
+impl Display for Optimization {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} steps, min / median / max", self.steps)?;
+        writeln!(f, "Decoded:   {}", self.decoded)?;
+        writeln!(f, "Optimized: {}", self.optimized)?;
+        write!(f, "{:.1}x faster", self.speedup())
+    }
+}
+
+/// The default program for `compare_optimized`: day 23's nested loop testing whether `b = 1000`
+/// is composite by trying every product `d * e`, about 8 million steps.
+pub const COMPOSITE_TEST: &str = "\
+set b 1000
+set f 1
+set d 2
+set e 2
+set g d
+mul g e
+sub g b
+jnz g 2
+set f 0
+sub e -1
+set g e
+sub g b
+jnz g -8
+sub d -1
+set g d
+sub g b
+jnz g -13
+";
+
+/// Runs a program that doesn't talk to others `runs` times, as decoded and optimized.
+pub fn compare_optimized(program: &[Instr], runs: usize) -> Result<Optimization> {
+    let mut decoded_times = Vec::with_capacity(runs);
+    let mut optimized_times = Vec::with_capacity(runs);
+    let mut steps = 0;
+    for _ in 0..runs {
+        let start = Instant::now();
+        let mut decoded = FastMachine::default();
+        steps = decoded.run(&Compiled::new(program))?;
+        decoded_times.push(start.elapsed());
+
+        let start = Instant::now();
+        let mut optimized = FastMachine::default();
+        optimized.run(&Compiled::optimized(program))?;
+        optimized_times.push(start.elapsed());
+
+        if decoded != optimized {
+            return Err(Error::InvalidInput(
+                "the optimized program ended up with different registers".to_owned(),
+            ));
+        }
+    }
+    let no_runs = || Error::InvalidInput("there must be at least one run".to_owned());
+    Ok(Optimization {
+        steps,
+        decoded: Stats::new(decoded_times).ok_or_else(no_runs)?,
+        optimized: Stats::new(optimized_times).ok_or_else(no_runs)?,
+    })
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub measurements: Vec<Measurement>,
+}
+
+impl Baseline {
+    pub fn get(&self, day: usize, part: Part) -> Option<&Measurement> {
+        self.measurements
+            .iter()
+            .find(|m| m.day == day && m.part == part)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_str(
+            &InputSource::File(path.to_owned()).read()?,
+        )?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string(self)?;
+        std::fs::write(path, format!("{json}\n")).map_err(|e| Error::Write(path.to_owned(), e))
+    }
+}
+
+/// Measurements, compared with a baseline when there is one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    pub measurements: Vec<Measurement>,
+    pub baseline: Option<Baseline>,
+    /// Relative slowdown of the median above which a measurement counts as a regression.
+    pub threshold: f64,
+}
+
+impl Report {
+    /// Relative change of the median compared to the baseline, `0.1` is 10% slower.
+    pub fn change(&self, measurement: &Measurement) -> Option<f64> {
+        let before = self
+            .baseline
+            .as_ref()?
+            .get(measurement.day, measurement.part)?
+            .median();
+        Some(measurement.median().as_secs_f64() / before.as_secs_f64().max(1e-9) - 1.0)
+    }
+
+    pub fn regressions(&self) -> impl Iterator<Item = &Measurement> {
+        self.measurements
+            .iter()
+            .filter(|m| self.change(m).is_some_and(|change| change > self.threshold))
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rows: Vec<[String; 6]> = self
+            .measurements
+            .iter()
+            .map(|m| {
+                let (baseline, change) = match self.change(m) {
+                    Some(change) => (
+                        self.baseline
+                            .as_ref()
+                            .and_then(|b| b.get(m.day, m.part))
+                            .map(|b| format_duration(b.median()))
+                            .unwrap_or_default(),
+                        format!(
+                            "{:+.1}%{}",
+                            change * 100.0,
+                            if change > self.threshold {
+                                " REGRESSION"
+                            } else {
+                                ""
+                            }
+                        ),
+                    ),
+                    None => ("-".to_owned(), "-".to_owned()),
+                };
+                [
+                    format!("{:02}", m.day),
+                    m.part.number().to_string(),
+                    m.parse.to_string(),
+                    m.solve.to_string(),
+                    baseline,
+                    change,
+                ]
+            })
+            .collect();
+        write_table(
+            f,
+            [
+                "Day",
+                "Part",
+                "Parse min / median / max",
+                "Solve min / median / max",
+                "Baseline",
+                "Change",
+            ],
+            &rows,
+        )?;
+        let total: Duration = self.measurements.iter().map(Measurement::median).sum();
+        write!(
+            f,
+            "{} parts, {} total median, {} regressions",
+            self.measurements.len(),
+            format_duration(total),
+            self.regressions().count()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Lines;
+    use crate::runner;
+    use aoc_helpers::scaffold::Parse;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    fn measurement(day: usize, solve_median: u64) -> Measurement {
+        Measurement {
+            day,
+            part: Part::One,
+            runs: 3,
+            parse: Stats::new(vec![ms(1), ms(1), ms(2)]).unwrap(),
+            solve: Stats::new(vec![
+                ms(solve_median - 1),
+                ms(solve_median),
+                ms(solve_median + 5),
+            ])
+            .unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        assert_eq!(Stats::new(vec![]), None);
+        let stats = Stats::new(vec![ms(5), ms(1), ms(3), ms(2)]).unwrap();
+        assert_eq!(
+            (stats.min, stats.median, stats.max),
+            (ms(1), Duration::from_micros(2500), ms(5))
+        );
+        assert_eq!(stats.to_string(), "1.00ms / 2.50ms / 5.00ms");
+    }
+
+    #[test]
+    fn test_measure() {
+        let day = runner::day(1).unwrap();
+        let m = measure(day, "1122", Part::One, 3).unwrap();
+        assert_eq!((m.day, m.part, m.runs), (1, Part::One, 3));
+        assert!(m.solve.min <= m.solve.median && m.solve.median <= m.solve.max);
+        assert!(measure(day, "1122", Part::One, 0).is_err());
+        assert!(measure(day, "12x", Part::One, 1).is_err());
+    }
+
+    #[test]
+    fn test_compare_optimized() {
+        let program = Lines::<Instr>::parse("set a 3\nsub a 1\njnz a -1").unwrap();
+        let optimization = compare_optimized(&program, 3).unwrap();
+        assert_eq!(optimization.steps, 7);
+        assert!(compare_optimized(&program, 0).is_err());
+        let program = Lines::<Instr>::parse("snd 1").unwrap();
+        assert!(compare_optimized(&program, 1).is_err());
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let baseline = Baseline {
+            measurements: vec![measurement(15, 10), measurement(17, 20)],
+        };
+        let json = serde_json::to_string(&baseline).unwrap();
+        assert_eq!(serde_json::from_str::<Baseline>(&json).unwrap(), baseline);
+        assert!(json.starts_with(
+            "{\"measurements\":[{\"day\":15,\"part\":1,\"runs\":3,\
+             \"parse_ns\":{\"min\":1000000,\"median\":1000000,\"max\":2000000},"
+        ));
+        assert!(serde_json::from_str::<Baseline>("{\"measurements\":[{}]}").is_err());
+        assert!(
+            serde_json::from_str::<Baseline>(&json.replace("\"part\":1", "\"part\":3")).is_err()
+        );
+    }
+
+    #[test]
+    fn test_regressions() {
+        let report = Report {
+            measurements: vec![measurement(15, 10), measurement(17, 30), measurement(22, 5)],
+            baseline: Some(Baseline {
+                measurements: vec![measurement(15, 10), measurement(17, 20)],
+            }),
+            threshold: 0.1,
+        };
+        assert_eq!(report.change(&report.measurements[0]), Some(0.0));
+        assert_eq!(report.change(&report.measurements[2]), None);
+        assert_eq!(
+            report.regressions().map(|m| m.day).collect::<Vec<_>>(),
+            vec![17]
+        );
+        assert!(report.to_string().contains("+47.6% REGRESSION"));
+    }
+}
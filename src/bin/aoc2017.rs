@@ -0,0 +1,1102 @@
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use advent_of_code_2017::bench::{self, Baseline};
+use advent_of_code_2017::error::Error;
+use advent_of_code_2017::format::{format_duration, nanos};
+use advent_of_code_2017::generate;
+use advent_of_code_2017::input::{InputSource, DEFAULT_INPUTS_DIR};
+use advent_of_code_2017::parallel::{self, Job};
+use advent_of_code_2017::parse::Lines;
+use advent_of_code_2017::runner::{self, Part, Run};
+use advent_of_code_2017::verify::{self, ANSWERS_FILE};
+use advent_of_code_2017::vm::arith::Arithmetic;
+use advent_of_code_2017::vm::asm;
+use advent_of_code_2017::vm::budget::{Budget, Outcome};
+use advent_of_code_2017::vm::cfg::Cfg;
+use advent_of_code_2017::vm::debugger::Debugger;
+use advent_of_code_2017::vm::decompile;
+use advent_of_code_2017::vm::duet::{Duet, Topology};
+use advent_of_code_2017::vm::profile;
+use advent_of_code_2017::vm::trace::{self, Replay, Trace};
+use advent_of_code_2017::vm::{listing, Dialect, Instr};
+use aoc_helpers::prelude::*;
+use serde::Serialize;
+
+const USAGE: &str = "\
+Usage: aoc2017 run [--day N|A-B|all] [--part 1|2] [--input PATH|-] [--inputs DIR] [--format text|json] [--jobs N]
+       aoc2017 verify [--day N|A-B|all] [--part 1|2] [--inputs DIR]
+       aoc2017 bench [run options] [--runs N] [--save PATH] [--compare PATH] [--threshold PCT]
+       aoc2017 generate [--day N|A-B|all] [--seed N] [--out DIR]
+       aoc2017 stress [--day N|A-B|all] [--part 1|2] [--seed N] [--seeds N]
+       aoc2017 duet [PATH|-] [--programs N] [--topology ring|pairwise|broadcast] [--threads] [--max-steps N] [--timeout SECS] [--overflow MODE] [--modulo MODE]
+       aoc2017 disasm [PATH|-] [--day 18|23]
+       aoc2017 decompile [PATH|-] [--day 18|23]
+       aoc2017 vmbench [PATH|-] [--runs N]
+       aoc2017 cfg [PATH|-] [--day 18|23] [--dot]
+       aoc2017 profile [PATH|-] [--day 18|23] [--programs N] [--topology ring|pairwise|broadcast] [--overflow MODE] [--modulo MODE]
+       aoc2017 debug [PATH] [--day 18|23] [--programs N] [--topology ring|pairwise|broadcast] [--overflow MODE] [--modulo MODE]
+       aoc2017 trace [PATH|-] [--day 18|23] [--programs N] [--topology ring|pairwise|broadcast] [--overflow MODE] [--modulo MODE]
+       aoc2017 replay TRACE
+       aoc2017 asm PATH|-
+
+  run        print the answers
+  verify     compare the answers with the known ones in DIR/answers.txt
+  bench      time parsing and solving over several runs
+  generate   print a random input, or write dayNN.txt files to DIR for use with --inputs
+  stress     run the days on random inputs and report errors and panics
+  duet       run a Duet program and report why it stopped (default: inputs/day18.txt)
+  disasm     print a day 18 or 23 program with instruction indices and jump targets
+  decompile  print a day 18 or 23 program as pseudo-code with loops and ifs
+  vmbench    time a program without snd and rcv with and without the optimizer (default: a nested loop from day 23)
+  cfg        print the basic blocks and loops of a day 18 or 23 program
+  profile    count how often every instruction of a day 18 or 23 program runs
+  debug      step through a day 18 or 23 program with commands read from stdin, see `help` there
+  trace      print every step of a day 18 or 23 program as a JSON line
+  replay     move forwards and backwards through a trace with commands read from stdin
+  asm        turn a program with labels, constants and comments into plain instructions
+
+  --day     day to run, an inclusive range of days or `all` (default: all)
+  --part    only run the given part (default: both)
+  --input   input for a single day, `-` reads stdin (default: DIR/dayNN.txt)
+  --inputs  directory with dayNN.txt files (default: inputs)
+  --format  `json` prints a JSON object per line with answers and timings (default: text)
+  --jobs    run the parts on N worker threads and report total and critical path time
+
+  --runs       how many times each part is run (default: 5)
+  --save       write the measurements to a JSON baseline
+  --compare    compare the medians with a JSON baseline
+  --threshold  slowdown in percent reported as a regression (default: 10)
+
+  --seed   seed of the first random input (default: 0)
+  --seeds  how many random inputs stress runs for each day (default: 10)
+  --out    directory to write the generated inputs to
+
+  --programs  how many copies of the program run, register p holds their index (default: 2)
+  --topology  who receives what a program sends (default: pairwise)
+  --threads   run every program on its own thread
+  --max-steps  stop duet after N steps of all programs together
+  --timeout    stop duet after SECS seconds
+  --overflow   what add, sub and mul do with results that don't fit 64 bits: checked fails,
+               wrapping or saturating (default: checked)
+  --modulo     truncated keeps the sign of the dividend like Rust's `%`, euclidean is never
+               negative (default: truncated)
+  --day       18 debugs Duet programs, 23 a single coprocessor program (default: 18)
+  --dot       print the blocks and loops as a Graphviz graph";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Format {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(anyhow::anyhow!("Unknown format {:?}", s)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct RunOptions {
+    days: RangeInclusive<usize>,
+    parts: Vec<Part>,
+    input: Option<InputSource>,
+    inputs_dir: PathBuf,
+    format: Format,
+    /// Worker threads, `None` runs the parts one after another.
+    jobs: Option<usize>,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            days: 1..=25,
+            parts: Part::BOTH.to_vec(),
+            input: None,
+            inputs_dir: PathBuf::from(DEFAULT_INPUTS_DIR),
+            format: Format::default(),
+            jobs: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct BenchOptions {
+    run: RunOptions,
+    runs: usize,
+    save: Option<PathBuf>,
+    compare: Option<PathBuf>,
+    threshold: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct GenerateOptions {
+    days: RangeInclusive<usize>,
+    parts: Vec<Part>,
+    seed: u64,
+    seeds: u64,
+    out: Option<PathBuf>,
+}
+
+impl Default for GenerateOptions {
+    fn default() -> Self {
+        Self {
+            days: 1..=25,
+            parts: Part::BOTH.to_vec(),
+            seed: 0,
+            seeds: 10,
+            out: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct DuetOptions {
+    input: InputSource,
+    programs: usize,
+    topology: Topology,
+    threads: bool,
+    budget: Budget,
+    arithmetic: Arithmetic,
+}
+
+fn parse_days(s: &str) -> anyhow::Result<RangeInclusive<usize>> {
+    let parse_day = |s: &str| -> anyhow::Result<usize> {
+        let day = s
+            .parse::<usize>()
+            .map_err(|e| anyhow::anyhow!("Invalid day {:?}: {}", s, e))?;
+        if runner::day(day).is_none() {
+            return Err(anyhow::anyhow!("There is no day {}", day));
+        }
+        Ok(day)
+    };
+    if s == "all" {
+        Ok(1..=25)
+    } else if let Some((first, last)) = s.split_once('-') {
+        let range = parse_day(first)?..=parse_day(last)?;
+        if range.is_empty() {
+            return Err(anyhow::anyhow!("Empty range of days: {}", s));
+        }
+        Ok(range)
+    } else {
+        let day = parse_day(s)?;
+        Ok(day..=day)
+    }
+}
+
+fn parse_run_options<I: Iterator<Item = String>>(mut args: I) -> anyhow::Result<RunOptions> {
+    let mut options = RunOptions::default();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow::anyhow!("Missing value for {}", arg))
+        };
+        match arg.as_str() {
+            "--day" => options.days = parse_days(&value()?)?,
+            "--part" => options.parts = vec![value()?.parse()?],
+            "--input" => options.input = Some(InputSource::from_arg(&value()?)),
+            "--inputs" => options.inputs_dir = PathBuf::from(value()?),
+            "--format" => options.format = value()?.parse()?,
+            "--jobs" => {
+                options.jobs = Some(
+                    value()?
+                        .parse()
+                        .ok()
+                        .filter(|jobs| *jobs > 0)
+                        .ok_or_else(|| anyhow::anyhow!("--jobs must be a positive number"))?,
+                )
+            }
+            _ => return Err(anyhow::anyhow!("Unknown argument: {}", arg)),
+        }
+    }
+    if options.input.is_some() && options.days.start() != options.days.end() {
+        return Err(anyhow::anyhow!(
+            "--input can only be used with a single day"
+        ));
+    }
+    Ok(options)
+}
+
+fn parse_bench_options<I: Iterator<Item = String>>(mut args: I) -> anyhow::Result<BenchOptions> {
+    let mut runs = 5;
+    let mut save = None;
+    let mut compare = None;
+    let mut threshold = 10.0;
+    let mut run_args = Vec::new();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow::anyhow!("Missing value for {}", arg))
+        };
+        match arg.as_str() {
+            "--runs" => {
+                runs = value()?
+                    .parse()
+                    .ok()
+                    .filter(|runs| *runs > 0)
+                    .ok_or_else(|| anyhow::anyhow!("--runs must be a positive number"))?
+            }
+            "--save" => save = Some(PathBuf::from(value()?)),
+            "--compare" => compare = Some(PathBuf::from(value()?)),
+            "--threshold" => {
+                threshold = value()?
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid --threshold: {}", e))?
+            }
+            // everything else is up to `parse_run_options`, values included
+            _ => run_args.push(arg),
+        }
+    }
+    Ok(BenchOptions {
+        run: parse_run_options(run_args.into_iter())?,
+        runs,
+        save,
+        compare,
+        threshold,
+    })
+}
+
+/// A line of `--format json` output about a single part.
+#[derive(Serialize)]
+struct PartLine<T> {
+    day: usize,
+    part: Part,
+    #[serde(flatten)]
+    body: T,
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+fn print_run(
+    day: usize,
+    part: Part,
+    result: Result<Run, Error>,
+    format: Format,
+) -> anyhow::Result<()> {
+    match (format, result) {
+        (Format::Text, Ok(run)) => {
+            println!("Day {:02} part {}: {}", day, part.number(), run.answer)
+        }
+        (Format::Json, Ok(run)) => println!(
+            "{}",
+            serde_json::to_string(&PartLine {
+                day,
+                part,
+                body: run
+            })?
+        ),
+        (Format::Text, Err(e)) => return Err(e.into()),
+        (Format::Json, Err(e)) => {
+            let error = PartLine {
+                day,
+                part,
+                body: ErrorBody {
+                    error: e.to_string(),
+                },
+            };
+            println!("{}", serde_json::to_string(&error)?);
+            return Err(e.into());
+        }
+    }
+    Ok(())
+}
+
+fn read_input(options: &RunOptions, day: usize) -> anyhow::Result<String> {
+    Ok(options
+        .input
+        .clone()
+        .unwrap_or_else(|| InputSource::for_day(&options.inputs_dir, day))
+        .read()?)
+}
+
+fn parse_generate_options<I: Iterator<Item = String>>(
+    mut args: I,
+) -> anyhow::Result<GenerateOptions> {
+    let mut options = GenerateOptions::default();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow::anyhow!("Missing value for {}", arg))
+        };
+        match arg.as_str() {
+            "--day" => options.days = parse_days(&value()?)?,
+            "--part" => options.parts = vec![value()?.parse()?],
+            "--seed" => {
+                options.seed = value()?
+                    .parse()
+                    .map_err(|e| anyhow::anyhow!("Invalid --seed: {}", e))?
+            }
+            "--seeds" => {
+                options.seeds = value()?
+                    .parse()
+                    .ok()
+                    .filter(|seeds| *seeds > 0)
+                    .ok_or_else(|| anyhow::anyhow!("--seeds must be a positive number"))?
+            }
+            "--out" => options.out = Some(PathBuf::from(value()?)),
+            _ => return Err(anyhow::anyhow!("Unknown argument: {}", arg)),
+        }
+    }
+    Ok(options)
+}
+
+/// `day` picks the default input.
+fn parse_duet_options<I: Iterator<Item = String>>(
+    mut args: I,
+    day: usize,
+) -> anyhow::Result<DuetOptions> {
+    let mut options = DuetOptions {
+        input: InputSource::for_day(DEFAULT_INPUTS_DIR.as_ref(), day),
+        programs: 2,
+        topology: Topology::Pairwise,
+        threads: false,
+        budget: Budget::default(),
+        arithmetic: Arithmetic::default(),
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| anyhow::anyhow!("Missing value for {}", arg))
+        };
+        match arg.as_str() {
+            "--programs" => {
+                options.programs = value()?
+                    .parse()
+                    .ok()
+                    .filter(|programs| *programs > 0)
+                    .ok_or_else(|| anyhow::anyhow!("--programs must be a positive number"))?
+            }
+            "--topology" => options.topology = value()?.parse()?,
+            "--threads" => options.threads = true,
+            "--max-steps" => {
+                options.budget.steps = Some(
+                    value()?
+                        .parse()
+                        .map_err(|_| anyhow::anyhow!("--max-steps must be a number"))?,
+                )
+            }
+            "--timeout" => {
+                let seconds: f64 = value()?
+                    .parse()
+                    .ok()
+                    .filter(|seconds: &f64| *seconds >= 0.0 && seconds.is_finite())
+                    .ok_or_else(|| anyhow::anyhow!("--timeout must be a number of seconds"))?;
+                options.budget.time = Some(Duration::from_secs_f64(seconds))
+            }
+            "--overflow" => options.arithmetic.overflow = value()?.parse()?,
+            "--modulo" => options.arithmetic.modulo = value()?.parse()?,
+            _ if !arg.starts_with("--") || arg == "-" => {
+                options.input = InputSource::from_arg(&arg)
+            }
+            _ => return Err(anyhow::anyhow!("Unknown argument: {}", arg)),
+        }
+    }
+    Ok(options)
+}
+
+/// Everything `parse_duet_options` takes apart from the input.
+const DUET_OPTIONS: &[&str] = &[
+    "--programs",
+    "--topology",
+    "--threads",
+    "--max-steps",
+    "--timeout",
+    "--overflow",
+    "--modulo",
+];
+
+/// Options for several programs talking to each other, a day 23 program runs alone.
+const DAY18_OPTIONS: &[&str] = &["--programs", "--topology", "--threads"];
+
+/// What profile, debug and trace take on top of the input and `--day`.
+const RUN_PROGRAM_OPTIONS: &[&str] = &["--programs", "--topology", "--overflow", "--modulo"];
+
+/// Duet options with `--day` picking the dialect, any of `DUET_OPTIONS` that `command` doesn't
+/// list in `accepted` is an error rather than ignored.
+fn parse_program_options<I: Iterator<Item = String>>(
+    args: I,
+    command: &str,
+    accepted: &[&str],
+) -> anyhow::Result<(Dialect, DuetOptions)> {
+    let mut args: Vec<String> = args.collect();
+    let mut day = 18;
+    if let Some(idx) = args.iter().position(|arg| arg == "--day") {
+        day = match args.get(idx + 1).map(String::as_str) {
+            Some("18") => 18,
+            Some("23") => 23,
+            _ => return Err(anyhow::anyhow!("--day must be 18 or 23")),
+        };
+        args.drain(idx..idx + 2);
+    }
+    for arg in &args {
+        if !DUET_OPTIONS.contains(&arg.as_str()) {
+            continue;
+        }
+        if !accepted.contains(&arg.as_str()) {
+            return Err(anyhow::anyhow!("{} doesn't take {}", command, arg));
+        }
+        if day == 23 && DAY18_OPTIONS.contains(&arg.as_str()) {
+            return Err(anyhow::anyhow!("{} only applies to day 18 programs", arg));
+        }
+    }
+    let options = parse_duet_options(args.into_iter(), day)?;
+    let dialect = if day == 18 {
+        Dialect::Duet
+    } else {
+        Dialect::Coprocessor
+    };
+    Ok((dialect, options))
+}
+
+fn parse_vmbench_options<I: Iterator<Item = String>>(
+    mut args: I,
+) -> anyhow::Result<(Option<InputSource>, usize)> {
+    let mut input = None;
+    let mut runs = 5;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--runs" => {
+                runs = args
+                    .next()
+                    .and_then(|runs| runs.parse().ok())
+                    .filter(|runs| *runs > 0)
+                    .ok_or_else(|| anyhow::anyhow!("--runs must be a positive number"))?
+            }
+            _ if !arg.starts_with("--") || arg == "-" => input = Some(InputSource::from_arg(&arg)),
+            _ => return Err(anyhow::anyhow!("Unknown argument: {}", arg)),
+        }
+    }
+    Ok((input, runs))
+}
+
+fn parse_debug_options<I: Iterator<Item = String>>(
+    args: I,
+) -> anyhow::Result<(Dialect, DuetOptions)> {
+    let (dialect, options) = parse_program_options(args, "debug", RUN_PROGRAM_OPTIONS)?;
+    if options.input == InputSource::Stdin {
+        return Err(anyhow::anyhow!(
+            "The debugger reads its commands from stdin, the program must be in a file"
+        ));
+    }
+    Ok((dialect, options))
+}
+
+fn parse_replay_options<I: Iterator<Item = String>>(mut args: I) -> anyhow::Result<PathBuf> {
+    match (args.next(), args.next()) {
+        (Some(path), None) if !path.starts_with('-') => Ok(PathBuf::from(path)),
+        _ => Err(anyhow::anyhow!(
+            "replay needs the path of a trace, it reads its commands from stdin"
+        )),
+    }
+}
+
+fn parse_asm_options<I: Iterator<Item = String>>(mut args: I) -> anyhow::Result<InputSource> {
+    match (args.next(), args.next()) {
+        (Some(arg), None) if !arg.starts_with("--") => Ok(InputSource::from_arg(&arg)),
+        _ => Err(anyhow::anyhow!("asm needs the path of a program or `-`")),
+    }
+}
+
+fn run(options: &RunOptions) -> anyhow::Result<()> {
+    if let Some(workers) = options.jobs {
+        return run_parallel(options, workers);
+    }
+    for number in options.days.clone() {
+        let day = runner::day(number).expect("days are validated when parsing");
+        let input = read_input(options, number)?;
+        for part in &options.parts {
+            print_run(number, *part, day.timed_run(&input, *part), options.format)?;
+        }
+    }
+    Ok(())
+}
+
+fn run_parallel(options: &RunOptions, workers: usize) -> anyhow::Result<()> {
+    let inputs = options
+        .days
+        .clone()
+        .map(|number| Ok((number, read_input(options, number)?)))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let jobs: Vec<Job> = inputs
+        .iter()
+        .flat_map(|(number, input)| {
+            let day = runner::day(*number).expect("days are validated when parsing");
+            options.parts.iter().map(move |part| Job {
+                day,
+                part: *part,
+                input,
+            })
+        })
+        .collect();
+
+    let mut error = None;
+    let summary = parallel::run_jobs(&jobs, workers, |idx, result| {
+        let job = &jobs[idx];
+        match print_run(job.day.number, job.part, result, options.format) {
+            Ok(()) => true,
+            Err(e) => {
+                error = Some(e);
+                false
+            }
+        }
+    });
+    if let Some(e) = error {
+        return Err(e);
+    }
+
+    let critical_path = summary.critical_path.map(|(idx, time)| (&jobs[idx], time));
+    match options.format {
+        Format::Text => {
+            print!(
+                "{} parts on {} workers in {}, {} busy",
+                jobs.len(),
+                workers,
+                format_duration(summary.wall_time),
+                format_duration(summary.busy_time)
+            );
+            match critical_path {
+                Some((job, time)) => println!(
+                    ", critical path day {:02} part {} with {}",
+                    job.day.number,
+                    job.part.number(),
+                    format_duration(time)
+                ),
+                None => println!(),
+            }
+        }
+        Format::Json => {
+            #[derive(Serialize)]
+            struct CriticalPath {
+                day: usize,
+                part: Part,
+                #[serde(with = "nanos")]
+                ns: Duration,
+            }
+            #[derive(Serialize)]
+            struct Summary {
+                parts: usize,
+                workers: usize,
+                #[serde(with = "nanos")]
+                total_ns: Duration,
+                #[serde(with = "nanos")]
+                busy_ns: Duration,
+                critical_path: Option<CriticalPath>,
+            }
+            let summary = Summary {
+                parts: jobs.len(),
+                workers,
+                total_ns: summary.wall_time,
+                busy_ns: summary.busy_time,
+                critical_path: critical_path.map(|(job, ns)| CriticalPath {
+                    day: job.day.number,
+                    part: job.part,
+                    ns,
+                }),
+            };
+            println!("{}", serde_json::to_string(&summary)?);
+        }
+    }
+    Ok(())
+}
+
+fn bench(options: &BenchOptions) -> anyhow::Result<()> {
+    if options.run.jobs.is_some() {
+        return Err(anyhow::anyhow!(
+            "bench runs one part at a time to keep timings stable"
+        ));
+    }
+    // load the baseline first so that a typo doesn't waste a whole benchmark run
+    let baseline = options.compare.as_deref().map(Baseline::load).transpose()?;
+    let mut measurements = Vec::new();
+    for number in options.run.days.clone() {
+        let day = runner::day(number).expect("days are validated when parsing");
+        let input = read_input(&options.run, number)?;
+        for part in &options.run.parts {
+            let measurement = bench::measure(day, &input, *part, options.runs)?;
+            eprintln!(
+                "Day {:02} part {}: {}",
+                number,
+                part.number(),
+                measurement.solve
+            );
+            measurements.push(measurement);
+        }
+    }
+    let report = bench::Report {
+        measurements,
+        baseline,
+        threshold: options.threshold / 100.0,
+    };
+    match options.run.format {
+        Format::Text => println!("{report}"),
+        Format::Json => println!(
+            "{}",
+            serde_json::to_string(&Baseline {
+                measurements: report.measurements.clone(),
+            })?
+        ),
+    }
+    if let Some(path) = &options.save {
+        Baseline {
+            measurements: report.measurements.clone(),
+        }
+        .save(path)?;
+    }
+    match report.regressions().count() {
+        0 => Ok(()),
+        count => Err(anyhow::anyhow!("{} parts got slower", count)),
+    }
+}
+
+fn verify(options: &RunOptions) -> anyhow::Result<()> {
+    if options.format != Format::Text {
+        return Err(anyhow::anyhow!("verify only prints text"));
+    }
+    if options.jobs.is_some() {
+        return Err(anyhow::anyhow!("verify runs one part at a time"));
+    }
+    if options.input.is_some() {
+        return Err(anyhow::anyhow!(
+            "verify reads inputs from --inputs, next to {}",
+            ANSWERS_FILE
+        ));
+    }
+    let report = verify::verify(options.days.clone(), &options.parts, &options.inputs_dir)?;
+    println!("{report}");
+    if report.is_success() {
+        Ok(())
+    } else {
+        Err(anyhow::anyhow!("Some answers don't match"))
+    }
+}
+
+fn generate(options: &GenerateOptions) -> anyhow::Result<()> {
+    match &options.out {
+        Some(dir) => Ok(generate::write_inputs(
+            options.days.clone(),
+            options.seed,
+            dir,
+        )?),
+        None if options.days.start() == options.days.end() => {
+            let input = generate::generate(*options.days.start(), options.seed)
+                .expect("days are validated when parsing");
+            println!("{input}");
+            Ok(())
+        }
+        None => Err(anyhow::anyhow!(
+            "Use --out to generate inputs for more than one day"
+        )),
+    }
+}
+
+fn stress(options: &GenerateOptions) -> anyhow::Result<()> {
+    let seeds = options.seed..options.seed.saturating_add(options.seeds);
+    let mut failures = 0;
+    for day in options.days.clone() {
+        let day_failures = generate::stress(day, seeds.clone(), &options.parts)?;
+        for failure in &day_failures {
+            println!(
+                "Day {:02} part {} seed {}: {}",
+                failure.day,
+                failure.part.number(),
+                failure.seed,
+                failure.message
+            );
+        }
+        eprintln!(
+            "Day {:02}: {} failures on {} inputs",
+            day,
+            day_failures.len(),
+            seeds.end - seeds.start
+        );
+        failures += day_failures.len();
+    }
+    match failures {
+        0 => Ok(()),
+        count => Err(anyhow::anyhow!("{} parts failed on random inputs", count)),
+    }
+}
+
+fn duet(options: &DuetOptions) -> anyhow::Result<()> {
+    let code = Dialect::Duet.parse_program(&options.input.read()?)?;
+    let mut duet = load_duet(Dialect::Duet, &code, options)?;
+    let result = if options.threads {
+        if options.budget != Budget::default() {
+            return Err(anyhow::anyhow!(
+                "--max-steps and --timeout can't be used with --threads"
+            ));
+        }
+        duet.run_threaded()
+    } else {
+        match duet.run_with(options.budget) {
+            Ok(Outcome::Finished(diagnosis)) => Ok(diagnosis),
+            Ok(Outcome::Exhausted(exhausted)) => {
+                print!("{}", exhausted.state);
+                return Err(anyhow::anyhow!(
+                    "Ran out of {} after {} steps",
+                    exhausted.limit,
+                    exhausted.steps
+                ));
+            }
+            Err(e) => Err(e),
+        }
+    };
+    match result {
+        Ok(diagnosis) => {
+            print!("{diagnosis}");
+            Ok(())
+        }
+        Err(e) => {
+            print!("{}", duet.diagnose());
+            Err(e.into())
+        }
+    }
+}
+
+fn disasm(dialect: Dialect, options: &DuetOptions) -> anyhow::Result<()> {
+    let code = dialect.parse_program(&options.input.read()?)?;
+    print!("{}", listing(&code));
+    Ok(())
+}
+
+fn lift(dialect: Dialect, options: &DuetOptions) -> anyhow::Result<()> {
+    let code = dialect.parse_program(&options.input.read()?)?;
+    print!("{}", decompile::decompile(&code));
+    Ok(())
+}
+
+fn cfg(dialect: Dialect, options: &DuetOptions, dot: bool) -> anyhow::Result<()> {
+    let code = dialect.parse_program(&options.input.read()?)?;
+    let cfg = Cfg::new(&code);
+    if dot {
+        print!("{}", cfg.to_dot(&code));
+    } else {
+        print!("{cfg}");
+    }
+    Ok(())
+}
+
+fn vmbench(input: Option<&InputSource>, runs: usize) -> anyhow::Result<()> {
+    let raw_input = match input {
+        Some(input) => input.read()?,
+        None => bench::COMPOSITE_TEST.to_owned(),
+    };
+    let code = Lines::<Instr>::parse(raw_input.trim_end())?;
+    println!("{}", bench::compare_optimized(&code, runs)?);
+    Ok(())
+}
+
+/// Copies of a Duet program, or a single coprocessor program with no one to talk to.
+fn load_duet(dialect: Dialect, code: &[Instr], options: &DuetOptions) -> anyhow::Result<Duet> {
+    let mut duet = match dialect {
+        Dialect::Duet => Duet::copies(code, options.programs, &options.topology)?,
+        Dialect::Coprocessor => {
+            // no register p either
+            let mut duet = Duet::copies(code, 1, &Topology::Pairwise)?;
+            duet.programs[0].machine.set('p', 0);
+            duet
+        }
+    };
+    for program in &mut duet.programs {
+        program.machine.arithmetic = options.arithmetic;
+    }
+    Ok(duet)
+}
+
+fn debug(dialect: Dialect, options: &DuetOptions) -> anyhow::Result<()> {
+    let code = dialect.parse_program(&options.input.read()?)?;
+    let duet = load_duet(dialect, &code, options)?;
+    Debugger::new(duet).repl(std::io::stdin().lock(), std::io::stdout())?;
+    Ok(())
+}
+
+fn profile(dialect: Dialect, options: &DuetOptions) -> anyhow::Result<()> {
+    let code = dialect.parse_program(&options.input.read()?)?;
+    let mut duet = load_duet(dialect, &code, options)?;
+    let (diagnosis, profiles) = profile::profile(&mut duet)?;
+    for (idx, profile) in profiles.iter().enumerate() {
+        if profiles.len() > 1 {
+            println!("Program {idx}:");
+        }
+        println!("{}", profile.annotate(&code));
+    }
+    print!("{diagnosis}");
+    Ok(())
+}
+
+fn trace(dialect: Dialect, options: &DuetOptions) -> anyhow::Result<()> {
+    let code = dialect.parse_program(&options.input.read()?)?;
+    let mut duet = load_duet(dialect, &code, options)?;
+    let (trace, result) = trace::record(&mut duet);
+    print!("{trace}");
+    result?;
+    Ok(())
+}
+
+fn replay(path: PathBuf) -> anyhow::Result<()> {
+    let trace = Trace::parse(&InputSource::File(path).read()?)?;
+    Replay::new(&trace).repl(std::io::stdin().lock(), std::io::stdout())?;
+    Ok(())
+}
+
+fn asm(input: &InputSource) -> anyhow::Result<()> {
+    for instr in asm::assemble(&input.read()?)? {
+        println!("{instr}");
+    }
+    Ok(())
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let result = match args.next().as_deref() {
+        Some("run") => parse_run_options(args).and_then(|options| run(&options)),
+        Some("verify") => parse_run_options(args).and_then(|options| verify(&options)),
+        Some("bench") => parse_bench_options(args).and_then(|options| bench(&options)),
+        Some("generate") => parse_generate_options(args).and_then(|options| generate(&options)),
+        Some("stress") => parse_generate_options(args).and_then(|options| stress(&options)),
+        Some("duet") => parse_duet_options(args, 18).and_then(|options| duet(&options)),
+        Some("disasm") => parse_program_options(args, "disasm", &[])
+            .and_then(|(dialect, options)| disasm(dialect, &options)),
+        Some("decompile") => parse_program_options(args, "decompile", &[])
+            .and_then(|(dialect, options)| lift(dialect, &options)),
+        Some("vmbench") => {
+            parse_vmbench_options(args).and_then(|(input, runs)| vmbench(input.as_ref(), runs))
+        }
+        Some("cfg") => {
+            let mut args: Vec<String> = args.collect();
+            let dot = args.iter().any(|arg| arg == "--dot");
+            args.retain(|arg| arg != "--dot");
+            parse_program_options(args.into_iter(), "cfg", &[])
+                .and_then(|(dialect, options)| cfg(dialect, &options, dot))
+        }
+        Some("profile") => parse_program_options(args, "profile", RUN_PROGRAM_OPTIONS)
+            .and_then(|(dialect, options)| profile(dialect, &options)),
+        Some("debug") => {
+            parse_debug_options(args).and_then(|(dialect, options)| debug(dialect, &options))
+        }
+        Some("trace") => parse_program_options(args, "trace", RUN_PROGRAM_OPTIONS)
+            .and_then(|(dialect, options)| trace(dialect, &options)),
+        Some("replay") => parse_replay_options(args).and_then(replay),
+        Some("asm") => parse_asm_options(args).and_then(|input| asm(&input)),
+        Some("help" | "--help" | "-h") => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        _ => Err(anyhow::anyhow!("Missing or unknown command\n\n{}", USAGE)),
+    };
+    if let Err(e) = result {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_of_code_2017::vm::arith::{Modulo, Overflow};
+
+    fn args(s: &str) -> impl Iterator<Item = String> + '_ {
+        s.split_whitespace().map(|s| s.to_owned())
+    }
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!(parse_days("18").unwrap(), 18..=18);
+        assert_eq!(parse_days("3-7").unwrap(), 3..=7);
+        assert_eq!(parse_days("all").unwrap(), 1..=25);
+        assert!(parse_days("0").is_err());
+        assert!(parse_days("26").is_err());
+        assert!(parse_days("7-3").is_err());
+    }
+
+    #[test]
+    fn test_parse_run_options() {
+        assert_eq!(parse_run_options(args("")).unwrap(), RunOptions::default());
+        let options = parse_run_options(args("--day 18 --part 2 --input -")).unwrap();
+        assert_eq!(options.days, 18..=18);
+        assert_eq!(options.parts, vec![Part::Two]);
+        assert_eq!(options.input, Some(InputSource::Stdin));
+        assert!(parse_run_options(args("--day 1-2 --input foo.txt")).is_err());
+        assert!(parse_run_options(args("--part 3")).is_err());
+        assert_eq!(
+            parse_run_options(args("--format json")).unwrap().format,
+            Format::Json
+        );
+        assert!(parse_run_options(args("--format yaml")).is_err());
+        assert!(parse_run_options(args("--day")).is_err());
+        assert_eq!(parse_run_options(args("--jobs 4")).unwrap().jobs, Some(4));
+        assert!(parse_run_options(args("--jobs 0")).is_err());
+        assert!(parse_run_options(args("--jobs many")).is_err());
+    }
+
+    #[test]
+    fn test_parse_bench_options() {
+        let options =
+            parse_bench_options(args("--day 15 --runs 3 --compare base.json --part 2")).unwrap();
+        assert_eq!(options.run.days, 15..=15);
+        assert_eq!(options.run.parts, vec![Part::Two]);
+        assert_eq!(options.runs, 3);
+        assert_eq!(options.compare, Some(PathBuf::from("base.json")));
+        assert_eq!(options.save, None);
+        assert_eq!(options.threshold, 10.0);
+        assert!(parse_bench_options(args("--runs 0")).is_err());
+        assert!(parse_bench_options(args("--bogus 1")).is_err());
+        // an unknown flag doesn't take the next one as its value
+        assert_eq!(
+            parse_bench_options(args("--bogus --runs 3"))
+                .unwrap_err()
+                .to_string(),
+            "Unknown argument: --bogus"
+        );
+        assert_eq!(
+            parse_bench_options(args("--format json --runs 2 --day 3"))
+                .unwrap()
+                .run
+                .days,
+            3..=3
+        );
+    }
+
+    #[test]
+    fn test_parse_generate_options() {
+        assert_eq!(
+            parse_generate_options(args("")).unwrap(),
+            GenerateOptions::default()
+        );
+        let options = parse_generate_options(args("--day 7 --seed 42 --out gen")).unwrap();
+        assert_eq!(options.days, 7..=7);
+        assert_eq!(options.seed, 42);
+        assert_eq!(options.out, Some(PathBuf::from("gen")));
+        assert_eq!(parse_generate_options(args("--seeds 3")).unwrap().seeds, 3);
+        assert!(parse_generate_options(args("--seeds 0")).is_err());
+        assert!(parse_generate_options(args("--seed -1")).is_err());
+        assert!(parse_generate_options(args("--runs 2")).is_err());
+    }
+
+    #[test]
+    fn test_parse_duet_options() {
+        let options = parse_duet_options(
+            args("prog.txt --programs 5 --topology broadcast --threads"),
+            18,
+        )
+        .unwrap();
+        assert_eq!(options.input, InputSource::from_arg("prog.txt"));
+        assert_eq!(options.programs, 5);
+        assert_eq!(options.topology, Topology::Broadcast);
+        assert!(options.threads);
+        assert_eq!(
+            parse_duet_options(args("-"), 18).unwrap().input,
+            InputSource::Stdin
+        );
+        assert!(parse_duet_options(args("--topology star"), 18).is_err());
+        assert!(parse_duet_options(args("--programs 0"), 18).is_err());
+        let options = parse_duet_options(args("--max-steps 1000 --timeout 1.5"), 18).unwrap();
+        assert_eq!(options.budget.steps, Some(1000));
+        assert_eq!(options.budget.time, Some(Duration::from_millis(1500)));
+        assert!(parse_duet_options(args("--timeout -1"), 18).is_err());
+        let options =
+            parse_duet_options(args("--overflow wrapping --modulo euclidean"), 18).unwrap();
+        assert_eq!(
+            options.arithmetic,
+            Arithmetic {
+                overflow: Overflow::Wrapping,
+                modulo: Modulo::Euclidean
+            }
+        );
+        assert!(parse_duet_options(args("--overflow never"), 18).is_err());
+    }
+
+    #[test]
+    fn test_parse_vmbench_options() {
+        assert_eq!(
+            parse_vmbench_options(args("prog.txt --runs 3")).unwrap(),
+            (Some(InputSource::from_arg("prog.txt")), 3)
+        );
+        assert_eq!(parse_vmbench_options(args("")).unwrap(), (None, 5));
+        assert!(parse_vmbench_options(args("--runs 0")).is_err());
+    }
+
+    #[test]
+    fn test_parse_debug_options() {
+        let (dialect, options) = parse_debug_options(args("--day 23")).unwrap();
+        assert_eq!(dialect, Dialect::Coprocessor);
+        assert_eq!(options.input, InputSource::for_day("inputs".as_ref(), 23));
+        let (dialect, options) = parse_debug_options(args("prog.txt --programs 3")).unwrap();
+        assert_eq!(dialect, Dialect::Duet);
+        assert_eq!(options.input, InputSource::from_arg("prog.txt"));
+        assert_eq!(options.programs, 3);
+        assert_eq!(
+            parse_debug_options(args("--programs 3 --day 23"))
+                .unwrap_err()
+                .to_string(),
+            "--programs only applies to day 18 programs"
+        );
+        assert!(parse_debug_options(args("--day 5")).is_err());
+        assert!(parse_debug_options(args("-")).is_err());
+        assert!(parse_debug_options(args("--threads")).is_err());
+    }
+
+    #[test]
+    fn test_parse_program_options() {
+        let (dialect, options) =
+            parse_program_options(args("--day 23 prog.txt"), "disasm", &[]).unwrap();
+        assert_eq!(dialect, Dialect::Coprocessor);
+        assert_eq!(options.input, InputSource::from_arg("prog.txt"));
+        assert_eq!(
+            parse_program_options(args("--overflow wrapping"), "cfg", &[])
+                .unwrap_err()
+                .to_string(),
+            "cfg doesn't take --overflow"
+        );
+        assert!(parse_program_options(args("--threads"), "trace", RUN_PROGRAM_OPTIONS).is_err());
+        assert!(
+            parse_program_options(args("--timeout 1"), "profile", RUN_PROGRAM_OPTIONS).is_err()
+        );
+        assert!(parse_program_options(
+            args("--day 23 --topology ring"),
+            "trace",
+            RUN_PROGRAM_OPTIONS
+        )
+        .is_err());
+        assert!(parse_program_options(
+            args("--day 23 --modulo euclidean"),
+            "profile",
+            RUN_PROGRAM_OPTIONS
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_parse_replay_options() {
+        assert_eq!(
+            parse_replay_options(args("run.jsonl")).unwrap(),
+            PathBuf::from("run.jsonl")
+        );
+        assert!(parse_replay_options(args("")).is_err());
+        assert!(parse_replay_options(args("-")).is_err());
+        assert!(parse_replay_options(args("a b")).is_err());
+    }
+
+    #[test]
+    fn test_parse_asm_options() {
+        assert_eq!(parse_asm_options(args("-")).unwrap(), InputSource::Stdin);
+        assert_eq!(
+            parse_asm_options(args("prog.asm")).unwrap(),
+            InputSource::from_arg("prog.asm")
+        );
+        assert!(parse_asm_options(args("")).is_err());
+        assert!(parse_asm_options(args("--day 18")).is_err());
+    }
+}
@@ -0,0 +1,90 @@
+//! Day 1: Inverse Captcha
+
+use aoc_helpers::prelude::*;
+
+use crate::error::{Error, Result};
+use crate::parse::Grid;
+use crate::solver::Solver;
+
+pub struct Day01;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Digit(pub usize);
+
+impl TryFrom<char> for Digit {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        value
+            .to_digit(10)
+            .map(|d| Self(d as usize))
+            .ok_or_else(|| anyhow::anyhow!("{:?} is not a digit", value))
+    }
+}
+
+fn captcha(input: &[Vec<Digit>]) -> Result<&[Digit]> {
+    input
+        .first()
+        .filter(|digits| !digits.is_empty())
+        .map(|digits| digits.as_slice())
+        .ok_or_else(|| Error::InvalidInput("there are no digits".to_owned()))
+}
+
+impl Solver for Day01 {
+    type Input = Grid<Digit>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn solve_part1(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part1> {
+        let digits = captcha(input)?;
+        let mut sum = 0;
+        for pair in digits.windows(2) {
+            if pair[0] == pair[1] {
+                sum += pair[0].0;
+            }
+        }
+        if digits.first() == digits.last() {
+            sum += digits[0].0;
+        }
+        Ok(sum)
+    }
+
+    fn solve_part2(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part2> {
+        let digits = captcha(input)?;
+        let count = digits.len();
+
+        Ok(digits
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, digit)| {
+                let other_idx = (idx + count / 2) % count;
+                if *digit == digits[other_idx] {
+                    Some(digit.0)
+                } else {
+                    None
+                }
+            })
+            .sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_part1, solve_part2};
+
+    #[test]
+    fn test_samples() {
+        assert_eq!(solve_part1::<Day01>("1122").unwrap(), 3);
+        assert_eq!(solve_part1::<Day01>("91212129").unwrap(), 9);
+        assert_eq!(solve_part2::<Day01>("123425").unwrap(), 4);
+        assert_eq!(
+            solve_part1::<Day01>("12x4").unwrap_err().to_string(),
+            "Parse error at line 1, column 3: 'x' is not a digit"
+        );
+    }
+}
@@ -0,0 +1,78 @@
+//! Day 2: Corruption Checksum
+
+use aoc_helpers::prelude::*;
+
+use crate::error::{Error, Result};
+use crate::parse::Lines;
+use crate::solver::Solver;
+
+pub struct Day02;
+
+impl Solver for Day02 {
+    type Input = Lines<VecFromWhitespaceSeparated<TrimAndParse<usize>>>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn solve_part1(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part1> {
+        input
+            .iter()
+            .enumerate()
+            .map(|(idx, row)| match (row.iter().max(), row.iter().min()) {
+                (Some(max), Some(min)) => Ok(max - min),
+                _ => Err(Error::InvalidInput(format!("row {} is empty", idx + 1))),
+            })
+            .sum()
+    }
+
+    fn solve_part2(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part2> {
+        input
+            .iter()
+            .enumerate()
+            .map(|(row_idx, row)| {
+                row.iter()
+                    .enumerate()
+                    .flat_map(|(idx, x)| {
+                        std::iter::repeat(*x).zip(row.split_at(idx + 1).1.iter().copied())
+                    })
+                    .filter_map(|(x, y)| {
+                        let (modulo, div) = if x == 0 || y == 0 {
+                            return None;
+                        } else if x <= y {
+                            (y % x, y / x)
+                        } else {
+                            (x % y, x / y)
+                        };
+                        if modulo == 0 {
+                            Some(div)
+                        } else {
+                            None
+                        }
+                    })
+                    .next()
+                    .ok_or_else(|| {
+                        Error::NoSolution(format!(
+                            "row {} has no evenly divisible pair",
+                            row_idx + 1
+                        ))
+                    })
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::solve_part2;
+
+    const SAMPLE: &str = "5 9 2 8\n9 4 7 3\n3 8 6 5";
+
+    #[test]
+    fn test_sample() {
+        assert_eq!(solve_part2::<Day02>(SAMPLE).unwrap(), 9);
+    }
+}
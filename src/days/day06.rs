@@ -0,0 +1,137 @@
+//! Day 6: Memory Reallocation
+
+use std::collections::{HashMap, HashSet};
+
+use aoc_helpers::{prelude::*, scaffold::Parse};
+
+use crate::error::{Error, Result};
+use crate::solver::Solver;
+
+pub struct Day06;
+
+pub struct SixteenInts;
+
+impl Parse for SixteenInts {
+    type Parsed = [usize; 16];
+
+    fn parse(raw_input: &str) -> anyhow::Result<Self::Parsed> {
+        let mut ints = [0; 16];
+        let mut count = 0;
+        let mut column = 1;
+        for part in raw_input.split(|c: char| c.is_ascii_whitespace()) {
+            if !part.is_empty() {
+                let int = part.parse::<usize>().map_err(|e| {
+                    Error::parse(None, Some(column), format!("Int parsing failed: {e}"))
+                })?;
+                if let Some(slot) = ints.get_mut(count) {
+                    *slot = int;
+                }
+                count += 1;
+            }
+            column += part.chars().count() + 1;
+        }
+        if count != ints.len() {
+            return Err(Error::parse(
+                None,
+                None,
+                format!("Expected {} numbers, found {count}", ints.len()),
+            )
+            .into());
+        }
+        Ok(ints)
+    }
+}
+
+pub fn reallocate<const N: usize>(blocks: &[usize; N]) -> [usize; N] {
+    let (max_idx, max_val) = blocks
+        .iter()
+        .enumerate()
+        .max_by_key(|(idx, val)| (*val, -(*idx as isize)))
+        .expect("there should be at least one element");
+    let mut new_blocks = *blocks;
+    new_blocks[max_idx] = 0;
+    for x in new_blocks.iter_mut() {
+        *x += max_val / N;
+    }
+    for offset in 0..(max_val % N) {
+        new_blocks[(max_idx + 1 + offset) % N] += 1;
+    }
+    new_blocks
+}
+
+pub fn find_reallocation_loop<const N: usize>(blocks: &[usize; N]) -> usize {
+    let mut seen = HashSet::new();
+    let mut steps = 0;
+    let mut blocks = *blocks;
+    seen.insert(blocks);
+    loop {
+        blocks = reallocate(&blocks);
+        steps += 1;
+        if !seen.insert(blocks) {
+            break;
+        }
+    }
+    steps
+}
+
+pub fn measure_reallocation_loop<const N: usize>(blocks: &[usize; N]) -> usize {
+    let mut seen = HashMap::new();
+    let mut steps = 0;
+    let mut blocks = *blocks;
+    seen.insert(blocks, 0);
+    loop {
+        blocks = reallocate(&blocks);
+        steps += 1;
+        if let Some(old_steps) = seen.insert(blocks, steps) {
+            return steps - old_steps;
+        }
+    }
+}
+
+impl Solver for Day06 {
+    type Input = SixteenInts;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn solve_part1(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part1> {
+        Ok(find_reallocation_loop(input))
+    }
+
+    fn solve_part2(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part2> {
+        Ok(measure_reallocation_loop(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reallocate() {
+        assert_eq!(reallocate(&[0, 2, 7, 0]), [2, 4, 1, 2]);
+        assert_eq!(reallocate(&[2, 4, 1, 2]), [3, 1, 2, 3]);
+        assert_eq!(reallocate(&[3, 1, 2, 3]), [0, 2, 3, 4]);
+        assert_eq!(reallocate(&[0, 2, 3, 4]), [1, 3, 4, 1]);
+        assert_eq!(reallocate(&[1, 3, 4, 1]), [2, 4, 1, 2]);
+    }
+
+    #[test]
+    fn test_sample() {
+        assert_eq!(find_reallocation_loop(&[0, 2, 7, 0]), 5);
+        assert_eq!(measure_reallocation_loop(&[0, 2, 7, 0]), 4);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |raw: &str| Error::from(SixteenInts::parse(raw).unwrap_err()).to_string();
+        assert!(parse("0 2\t7 x").starts_with("Parse error at column 7: Int parsing failed"));
+        assert_eq!(
+            parse("0 2 7 0"),
+            "Parse error: Expected 16 numbers, found 4"
+        );
+    }
+}
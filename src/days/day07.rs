@@ -0,0 +1,352 @@
+//! Day 7: Recursive Circus
+
+use std::collections::{HashMap, HashSet};
+
+use aoc_helpers::prelude::*;
+
+use crate::error::{Error, Result};
+use crate::solver::Solver;
+
+pub struct Day07;
+
+#[derive(Clone, Debug)]
+pub struct Program<'a> {
+    pub name: &'a str,
+    pub weight: usize,
+    pub subs: Vec<&'a str>,
+}
+
+impl<'a> Program<'a> {
+    pub fn parse(s: &'a str) -> Result<Self, anyhow::Error> {
+        lazy_static::lazy_static! {
+            // vpbdpfm (74) -> ndegtj, wnwxs
+            static ref RE: regex::Regex = regex::Regex::new(r"(\w+) \((\d+)\)( -> ([\w, ]+))?").unwrap();
+        }
+
+        if let Some(caps) = RE.captures(s) {
+            Ok(Self {
+                name: caps
+                    .get(1)
+                    .ok_or_else(|| anyhow::anyhow!("Getting name failed"))?
+                    .as_str(),
+                weight: caps
+                    .get(2)
+                    .ok_or_else(|| anyhow::anyhow!("Getting weight failed"))?
+                    .as_str()
+                    .parse::<usize>()
+                    .map_err(|e| anyhow::anyhow!("Field 'weight' parsing error: {}", e))?,
+                subs: caps
+                    .get(4)
+                    .map(|m| m.as_str().split(", ").collect::<Vec<_>>())
+                    .unwrap_or_default(),
+            })
+        } else {
+            Err(anyhow::anyhow!("Regex didn't match"))
+        }
+    }
+
+    pub fn parse_all(s: &'a str) -> Result<Vec<Self>> {
+        s.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(idx, line)| Program::parse(line).map_err(|e| Error::from(e).at_line(idx + 1)))
+            .collect()
+    }
+}
+
+pub struct Programs<'a> {
+    programs: Vec<Program<'a>>,
+    bottom: &'a str,
+    name_to_idx: HashMap<&'a str, usize>,
+    name_to_tree_weight: HashMap<&'a str, usize>,
+}
+
+impl<'a> Programs<'a> {
+    /// Checks that the programs form a single tower: one bottom program holding up all the others.
+    pub fn new(programs: Vec<Program<'a>>) -> Result<Self> {
+        let mut name_to_idx: HashMap<&'a str, usize> = Default::default();
+        let mut name_to_parent_name: HashMap<&'a str, &'a str> = Default::default();
+        for (idx, program) in programs.iter().enumerate() {
+            if name_to_idx.insert(program.name, idx).is_some() {
+                return Err(Error::InvalidInput(format!(
+                    "program {} is described twice",
+                    program.name
+                )));
+            }
+            for sub in &program.subs {
+                if let Some(first_parent) = name_to_parent_name.insert(sub, program.name) {
+                    return Err(Error::DuplicateParent {
+                        program: sub.to_string(),
+                        first_parent: first_parent.to_owned(),
+                        second_parent: program.name.to_owned(),
+                    });
+                }
+            }
+        }
+        if let Some(unknown) = name_to_parent_name
+            .keys()
+            .find(|name| !name_to_idx.contains_key(*name))
+        {
+            return Err(Error::InvalidInput(format!(
+                "program {unknown} is held but never described"
+            )));
+        }
+
+        let mut bottoms = programs
+            .iter()
+            .map(|program| program.name)
+            .filter(|name| !name_to_parent_name.contains_key(name));
+        let bottom = match (bottoms.next(), bottoms.next()) {
+            (Some(bottom), None) => bottom,
+            (None, _) if programs.is_empty() => {
+                return Err(Error::InvalidInput("there are no programs".to_owned()))
+            }
+            (None, _) => {
+                return Err(Error::InvalidInput(
+                    "every program is held by another one".to_owned(),
+                ))
+            }
+            (Some(first), Some(second)) => {
+                return Err(Error::InvalidInput(format!(
+                    "programs {first} and {second} both stand at the bottom"
+                )))
+            }
+        };
+        // every program has at most one parent, so anything the bottom doesn't hold up is in a cycle
+        let mut held_up = HashSet::new();
+        let mut stack = vec![bottom];
+        while let Some(name) = stack.pop() {
+            held_up.insert(name);
+            if let Some(&idx) = name_to_idx.get(name) {
+                stack.extend(&programs[idx].subs);
+            }
+        }
+        if let Some(detached) = programs
+            .iter()
+            .find(|program| !held_up.contains(program.name))
+        {
+            return Err(Error::InvalidInput(format!(
+                "program {} doesn't stand on {bottom}, it's held up by a cycle",
+                detached.name
+            )));
+        }
+
+        Ok(Self {
+            programs,
+            bottom,
+            name_to_idx,
+            name_to_tree_weight: Default::default(),
+        })
+    }
+
+    pub fn find_bottom(&self) -> &'a str {
+        self.bottom
+    }
+
+    fn index_of(&self, name: &str) -> Result<usize> {
+        self.name_to_idx.get(name).copied().ok_or_else(|| {
+            Error::InvalidInput(format!("program {name} is held but never described"))
+        })
+    }
+
+    fn tree_weight(&self, name: &str) -> Result<usize> {
+        self.name_to_tree_weight
+            .get(name)
+            .copied()
+            .ok_or_else(|| Error::NoSolution(format!("the tower on {name} wasn't weighed")))
+    }
+
+    pub fn fill_tree_weight(&mut self, bottom_tree_name: &str) -> Result<()> {
+        let mut stack = vec![self.index_of(bottom_tree_name)?];
+        while let Some(idx) = stack.pop() {
+            let program = &self.programs[idx];
+            let sub_weight = program
+                .subs
+                .iter()
+                .map(|sub_name| self.name_to_tree_weight.get(sub_name).copied())
+                .reduce(|accum, item| {
+                    if let (Some(a), Some(i)) = (accum, item) {
+                        Some(a + i)
+                    } else {
+                        None
+                    }
+                })
+                // first level option being None means there are no sub programs
+                .unwrap_or(Some(0));
+            if let Some(sub_weight) = sub_weight {
+                // we have weights of all the sub trees
+                self.name_to_tree_weight
+                    .insert(program.name, program.weight + sub_weight);
+            } else {
+                // something is missing -> we'll have to come back to this node so push it onto stack
+                stack.push(idx);
+                // push all the missing nodes
+                for sub_name in &program.subs {
+                    if !self.name_to_tree_weight.contains_key(sub_name) {
+                        stack.push(self.index_of(sub_name)?);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn is_balanced(&self, program: &Program) -> bool {
+        let mut weights = program
+            .subs
+            .iter()
+            .map(|sub| self.name_to_tree_weight.get(sub));
+        let first = weights.next();
+        weights.all(|weight| Some(weight) == first)
+    }
+
+    pub fn find_unbalanced_weight(&self) -> Result<usize> {
+        for program in &self.programs {
+            if !program.subs.is_empty() {
+                let mut weights: HashMap<usize, usize> = Default::default();
+                for sub in &program.subs {
+                    *weights.entry(self.tree_weight(sub)?).or_default() += 1;
+                }
+                if weights.len() > 1 {
+                    let ambiguous = || {
+                        Error::NoSolution(format!(
+                            "can't tell which program held by {} is unbalanced",
+                            program.name
+                        ))
+                    };
+                    let weight = *weights
+                        .iter()
+                        .find(|(_, v)| **v == 1)
+                        .ok_or_else(ambiguous)?
+                        .0;
+                    let other_weight = weights
+                        .into_iter()
+                        .find(|(_, v)| *v > 1)
+                        .ok_or_else(ambiguous)?
+                        .0;
+                    let sub_name = program
+                        .subs
+                        .iter()
+                        .find(|sub_name| self.name_to_tree_weight.get(*sub_name) == Some(&weight))
+                        .ok_or_else(ambiguous)?;
+                    let sub = &self.programs[self.index_of(sub_name)?];
+                    if !self.is_balanced(sub) {
+                        // the program that's off is further up, its parents are unbalanced too
+                        continue;
+                    }
+                    return (other_weight + sub.weight)
+                        .checked_sub(weight)
+                        .ok_or_else(|| {
+                            Error::NoSolution(format!(
+                                "program {sub_name} would need a negative weight"
+                            ))
+                        });
+                }
+            }
+        }
+        Err(Error::NoSolution("the tower is balanced".to_owned()))
+    }
+}
+
+impl Solver for Day07 {
+    type Input = String;
+    type Part1 = String;
+    type Part2 = usize;
+
+    fn solve_part1(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part1> {
+        let programs = Programs::new(Program::parse_all(input)?)?;
+        Ok(programs.find_bottom().to_owned())
+    }
+
+    fn solve_part2(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part2> {
+        let mut programs = Programs::new(Program::parse_all(input)?)?;
+        let bottom = programs.find_bottom();
+        programs.fill_tree_weight(bottom)?;
+        programs.find_unbalanced_weight()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_part1, solve_part2};
+
+    const SAMPLE: &str = "pbga (66)
+xhth (57)
+ebii (61)
+havc (66)
+ktlj (57)
+fwft (72) -> ktlj, cntj, xhth
+qoyq (66)
+padx (45) -> pbga, havc, qoyq
+tknk (41) -> ugml, padx, fwft
+jptl (61)
+ugml (68) -> gyxo, ebii, jptl
+gyxo (61)
+cntj (57)";
+
+    #[test]
+    fn test_sample() {
+        assert_eq!(solve_part1::<Day07>(SAMPLE).unwrap(), "tknk");
+        assert_eq!(solve_part2::<Day07>(SAMPLE).unwrap(), 60);
+    }
+
+    #[test]
+    fn test_unbalanced_parent_comes_first() {
+        // both root and a hold an odd one out, but only a1 needs a different weight
+        let input = "root (1) -> a, b, c
+a (1) -> a1, a2, a3
+b (1) -> b1, b2, b3
+c (1) -> c1, c2, c3
+a1 (6)
+a2 (5)
+a3 (5)
+b1 (5)
+b2 (5)
+b3 (5)
+c1 (5)
+c2 (5)
+c3 (5)";
+        assert_eq!(solve_part2::<Day07>(input).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            solve_part1::<Day07>("a (1) -> b\nc (2) -> b\nb (3)")
+                .unwrap_err()
+                .to_string(),
+            "Program b is held by both a and c"
+        );
+        assert_eq!(
+            solve_part1::<Day07>("a (1)\nb 2").unwrap_err().to_string(),
+            "Parse error at line 2: Regex didn't match"
+        );
+    }
+
+    #[test]
+    fn test_not_a_single_tower() {
+        assert_eq!(
+            solve_part2::<Day07>("a (1)\nb (1) -> c\nc (1)")
+                .unwrap_err()
+                .to_string(),
+            "Invalid input: programs a and b both stand at the bottom"
+        );
+        assert_eq!(
+            solve_part2::<Day07>("a (1) -> b\nb (1)\nc (1) -> d\nd (1) -> c")
+                .unwrap_err()
+                .to_string(),
+            "Invalid input: program c doesn't stand on a, it's held up by a cycle"
+        );
+        assert_eq!(
+            solve_part1::<Day07>("a (1) -> b\nb (1) -> a")
+                .unwrap_err()
+                .to_string(),
+            "Invalid input: every program is held by another one"
+        );
+    }
+}
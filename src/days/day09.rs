@@ -0,0 +1,102 @@
+//! Day 9: Stream Processing
+
+use crate::error::Result;
+use crate::solver::Solver;
+
+pub struct Day09;
+
+impl Solver for Day09 {
+    type Input = String;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn solve_part1(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part1> {
+        let mut depth: usize = 0;
+        let mut score = 0;
+        let mut ignore_next = false;
+        let mut in_garbage = false;
+        for c in input.chars() {
+            if ignore_next {
+                ignore_next = false;
+            } else if c == '!' {
+                ignore_next = true;
+            } else if in_garbage {
+                if c == '>' {
+                    in_garbage = false;
+                }
+            } else if c == '<' {
+                in_garbage = true;
+            } else if c == '}' {
+                score += depth;
+                depth = depth.saturating_sub(1);
+            } else if c == '{' {
+                depth += 1;
+            }
+        }
+        Ok(score)
+    }
+
+    fn solve_part2(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part2> {
+        let mut score = 0;
+        let mut ignore_next = false;
+        let mut in_garbage = false;
+        for c in input.chars() {
+            if ignore_next {
+                ignore_next = false;
+            } else if c == '!' {
+                ignore_next = true;
+            } else if in_garbage {
+                if c == '>' {
+                    in_garbage = false;
+                } else {
+                    score += 1
+                }
+            } else if c == '<' {
+                in_garbage = true;
+            }
+        }
+        Ok(score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_part1, solve_part2};
+
+    #[test]
+    fn test_part1_samples() {
+        assert_eq!(solve_part1::<Day09>("{}").unwrap(), 1);
+        assert_eq!(solve_part1::<Day09>("{{{}}}").unwrap(), 6);
+        assert_eq!(solve_part1::<Day09>("{{},{}}").unwrap(), 5);
+        assert_eq!(solve_part1::<Day09>("{{{},{},{{}}}}").unwrap(), 16);
+        assert_eq!(solve_part1::<Day09>("{<a>,<a>,<a>,<a>}").unwrap(), 1);
+        assert_eq!(
+            solve_part1::<Day09>("{{<ab>},{<ab>},{<ab>},{<ab>}}").unwrap(),
+            9
+        );
+        assert_eq!(
+            solve_part1::<Day09>("{{<!!>},{<!!>},{<!!>},{<!!>}}").unwrap(),
+            9
+        );
+        assert_eq!(
+            solve_part1::<Day09>("{{<a!>},{<a!>},{<a!>},{<ab>}}").unwrap(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_part2_samples() {
+        assert_eq!(solve_part2::<Day09>("<>").unwrap(), 0);
+        assert_eq!(solve_part2::<Day09>("<random characters>").unwrap(), 17);
+        assert_eq!(solve_part2::<Day09>("<<<<>").unwrap(), 3);
+        assert_eq!(solve_part2::<Day09>("<{!>}>").unwrap(), 2);
+        assert_eq!(solve_part2::<Day09>("<!!>").unwrap(), 0);
+        assert_eq!(solve_part2::<Day09>("<!!!>>").unwrap(), 0);
+        assert_eq!(solve_part2::<Day09>("<{o'i!a,<{i<a>").unwrap(), 10);
+    }
+}
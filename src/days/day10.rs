@@ -0,0 +1,61 @@
+//! Day 10: Knot Hash
+
+use aoc_helpers::{prelude::*, scaffold::Parse};
+
+use crate::error::{Error, Result};
+use crate::knot_hash::{dense_to_sparse, knot_hash_list};
+use crate::parse::CommaSeparated;
+use crate::solver::Solver;
+
+pub struct Day10;
+
+pub fn vec_to_hex(v: Vec<usize>) -> String {
+    v.into_iter().map(|n| format!("{n:02x}")).collect()
+}
+
+impl Solver for Day10 {
+    type Input = String;
+    type Part1 = usize;
+    type Part2 = String;
+
+    fn solve_part1(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part1> {
+        let lengths = CommaSeparated::<TrimAndParse<usize>>::parse(input)?;
+        if let Some(length) = lengths.iter().find(|length| **length > 256) {
+            return Err(Error::InvalidInput(format!(
+                "length {length} is longer than the list"
+            )));
+        }
+        let lst = knot_hash_list::<256, 1>(&lengths);
+        Ok(lst[0] * lst[1])
+    }
+
+    fn solve_part2(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part2> {
+        let mut lengths: Vec<usize> = input.chars().map(|c| c as u8 as usize).collect();
+        lengths.extend_from_slice(&[17, 31, 73, 47, 23]);
+        let lst = knot_hash_list::<256, 64>(&lengths);
+        Ok(vec_to_hex(dense_to_sparse(&lst)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::solve_part1;
+
+    #[test]
+    fn test_proper_part1() {
+        assert_eq!(
+            solve_part1::<Day10>(include_str!("../../inputs/day10.txt")).unwrap(),
+            1980
+        );
+    }
+
+    #[test]
+    fn test_vec_to_hex() {
+        assert_eq!(vec_to_hex(vec![64, 7, 255]), "4007ff".to_owned());
+    }
+}
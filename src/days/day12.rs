@@ -0,0 +1,98 @@
+//! Day 12: Digital Plumber
+
+use std::{collections::HashSet, str::FromStr};
+
+use aoc_helpers::prelude::*;
+
+use crate::error::{Error, Result};
+use crate::parse::Lines;
+use crate::solver::Solver;
+
+pub struct Day12;
+
+#[derive(Clone, Debug)]
+pub struct Node {
+    pub idx: usize,
+    pub vertices: Vec<usize>,
+}
+
+impl FromStr for Node {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(" <-> ");
+        let idx: usize = parts
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Missing id"))?
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Error parsing id: {}", e))?;
+        let vertices: Vec<usize> = parts
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Missing vertices"))?
+            .split(", ")
+            .map(|s| {
+                s.parse()
+                    .map_err(|e| anyhow::anyhow!("Error parsing vertex id: {}", e))
+            })
+            .collect::<Result<Vec<usize>, _>>()?;
+        Ok(Self { idx, vertices })
+    }
+}
+
+/// `find_group` indexes nodes by their id, so ids must match positions.
+pub fn check_nodes(nodes: &[Node]) -> Result<()> {
+    for (position, node) in nodes.iter().enumerate() {
+        if node.idx != position {
+            return Err(Error::InvalidInput(format!(
+                "node {} is described in place of node {position}",
+                node.idx
+            )));
+        }
+        if let Some(vertex) = node.vertices.iter().find(|v| **v >= nodes.len()) {
+            return Err(Error::InvalidInput(format!(
+                "node {position} is connected to unknown node {vertex}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub fn find_group(nodes: &[Node], starting_idx: usize) -> HashSet<usize> {
+    let mut visited: HashSet<usize> = Default::default();
+    let mut stack: Vec<usize> = Default::default();
+    stack.push(starting_idx);
+    while let Some(idx) = stack.pop() {
+        if visited.insert(idx) {
+            stack.extend_from_slice(&nodes[idx].vertices);
+        }
+    }
+    visited
+}
+
+impl Solver for Day12 {
+    type Input = Lines<Node>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn solve_part1(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part1> {
+        check_nodes(input)?;
+        Ok(find_group(input, 0).len())
+    }
+
+    fn solve_part2(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part2> {
+        check_nodes(input)?;
+        let mut all_visited: HashSet<usize> = Default::default();
+        let mut groups_count = 0;
+        for idx in 0..input.len() {
+            if !all_visited.contains(&idx) {
+                groups_count += 1;
+                all_visited.extend(find_group(input, idx));
+            }
+        }
+        Ok(groups_count)
+    }
+}
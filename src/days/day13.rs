@@ -0,0 +1,102 @@
+//! Day 13: Packet Scanners
+
+use aoc_helpers::prelude::*;
+use rematch::rematch;
+
+use crate::error::{Error, Result};
+use crate::parse::Lines;
+use crate::solver::Solver;
+
+pub struct Day13;
+
+#[derive(Clone, Copy, Debug)]
+#[rematch(r"(\d+): (\d+)")]
+pub struct Layer {
+    pub depth: usize,
+    pub range: usize,
+}
+
+impl Layer {
+    /// Picoseconds it takes the scanner to get back to the top.
+    pub fn period(&self) -> Result<usize> {
+        match self.range {
+            0 => Err(Error::InvalidInput(format!(
+                "layer {} has no range",
+                self.depth
+            ))),
+            1 => Ok(1),
+            range => Ok(range * 2 - 2),
+        }
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl Solver for Day13 {
+    type Input = Lines<Layer>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn solve_part1(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part1> {
+        input
+            .iter()
+            .map(|layer| {
+                Ok(if layer.depth % layer.period()? == 0 {
+                    layer.depth * layer.range
+                } else {
+                    0
+                })
+            })
+            .sum()
+    }
+
+    fn solve_part2(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part2> {
+        let periods = input
+            .iter()
+            .map(|layer| Ok((layer.depth, layer.period()?)))
+            .collect::<Result<Vec<_>>>()?;
+        // the scanners are back where they started after the lcm of their periods
+        let cycle = periods.iter().try_fold(1usize, |lcm, (_, period)| {
+            (lcm / gcd(lcm, *period)).checked_mul(*period)
+        });
+        for delay in 1..cycle.unwrap_or(usize::MAX) {
+            if !periods
+                .iter()
+                .any(|(depth, period)| (delay + depth) % period == 0)
+            {
+                return Ok(delay);
+            }
+        }
+        Err(Error::NoSolution(
+            "the packet gets caught whatever the delay".to_owned(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_part1, solve_part2};
+
+    const SAMPLE: &str = concat!("0: 3\n", "1: 2\n", "4: 4\n", "6: 4",);
+
+    #[test]
+    fn test_sample() {
+        assert_eq!(solve_part1::<Day13>(SAMPLE).unwrap(), 24);
+        assert_eq!(solve_part2::<Day13>(SAMPLE).unwrap(), 10);
+        assert!(matches!(
+            solve_part2::<Day13>("0: 2\n1: 2"),
+            Err(Error::NoSolution(_))
+        ));
+    }
+}
@@ -0,0 +1,117 @@
+//! Day 14: Disk Defragmentation
+
+use aoc_helpers::{prelude::*, scaffold::Parse, tile_map::TileMap};
+
+use crate::error::{Error, Result};
+use crate::knot_hash::knot_hash;
+use crate::solver::Solver;
+
+pub struct Day14;
+
+pub struct TileMapHasher;
+
+pub fn u8_to_bools(b: u8) -> [bool; 8] {
+    [
+        b & 0b10000000 != 0,
+        b & 0b01000000 != 0,
+        b & 0b00100000 != 0,
+        b & 0b00010000 != 0,
+        b & 0b00001000 != 0,
+        b & 0b00000100 != 0,
+        b & 0b00000010 != 0,
+        b & 0b00000001 != 0,
+    ]
+}
+
+pub fn u8s_to_bools(u8s: &[u8]) -> Vec<bool> {
+    u8s.iter()
+        .flat_map(|b| u8_to_bools(*b).into_iter())
+        .collect()
+}
+
+impl Parse for TileMapHasher {
+    type Parsed = TileMap<bool>;
+
+    fn parse(raw_input: &str) -> anyhow::Result<Self::Parsed> {
+        let key = raw_input.trim();
+        if let Some(line) = key.lines().nth(1) {
+            return Err(
+                Error::parse(Some(2), None, format!("Unexpected {line:?} after the key")).into(),
+            );
+        }
+        let tiles: Vec<Vec<bool>> = (0..128)
+            .map(|row| u8s_to_bools(&knot_hash(&format!("{key}-{row}"))))
+            .collect();
+        Ok(TileMap::from(tiles))
+    }
+}
+
+impl Solver for Day14 {
+    type Input = TileMapHasher;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn solve_part1(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part1> {
+        Ok(input.iter().filter(|b| *b).count())
+    }
+
+    fn solve_part2(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part2> {
+        let mut visited: Vec<Vec<bool>> = vec![vec![false; 128]; 128];
+        let mut regions_count = 0;
+        let mut stack: Vec<(isize, isize)> = Default::default();
+        for row in 0..128 {
+            for col in 0..128 {
+                if input.get(row, col).unwrap() && !visited[row as usize][col as usize] {
+                    regions_count += 1;
+
+                    // search + mark as visited
+                    stack.push((row, col));
+                    visited[row as usize][col as usize] = true;
+                    while let Some((row, col)) = stack.pop() {
+                        for (drow, dcol) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                            let crow = row + drow;
+                            let ccol = col + dcol;
+                            if input.get(crow, ccol).unwrap_or_default()
+                                && !visited[crow as usize][ccol as usize]
+                            {
+                                stack.push((crow, ccol));
+                                visited[crow as usize][ccol as usize] = true;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(regions_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_part1, solve_part2};
+
+    const SAMPLE: &str = "flqrgnkx";
+
+    #[test]
+    fn test_sample() {
+        assert_eq!(solve_part1::<Day14>(SAMPLE).unwrap(), 8108);
+        assert_eq!(solve_part2::<Day14>(SAMPLE).unwrap(), 1242);
+    }
+
+    #[test]
+    fn test_u8s_to_bools() {
+        assert_eq!(
+            u8s_to_bools(&[0xa0, 0xc2, 0x01, 0x70]),
+            vec![
+                true, false, true, false, false, false, false, false, true, true, false, false,
+                false, false, true, false, false, false, false, false, false, false, false, true,
+                false, true, true, true, false, false, false, false,
+            ]
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! Day 15: Dueling Generators
+
+use aoc_helpers::prelude::*;
+use rematch::rematch;
+
+use crate::error::{Error, Result};
+use crate::parse::Lines;
+use crate::solver::Solver;
+
+pub struct Day15;
+
+#[derive(Clone, Copy, Debug)]
+#[rematch]
+pub enum Kind {
+    #[rematch(r"A")]
+    A,
+    #[rematch(r"B")]
+    B,
+}
+
+impl Kind {
+    pub const fn factor(&self) -> u64 {
+        match self {
+            Kind::A => 16807,
+            Kind::B => 48271,
+        }
+    }
+
+    pub const fn criteria(&self) -> u32 {
+        match self {
+            Kind::A => 4,
+            Kind::B => 8,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+#[rematch(r"Generator (A|B) starts with (\d+)")]
+pub struct Generator {
+    pub kind: Kind,
+    pub v: u32,
+}
+
+impl Iterator for Generator {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.v = (((self.v as u64) * self.kind.factor()) % 2147483647) as u32;
+        Some(self.v)
+    }
+}
+
+pub fn pair(generators: &[Generator]) -> Result<(Generator, Generator)> {
+    match generators {
+        [a, b] => Ok((*a, *b)),
+        _ => Err(Error::InvalidInput(format!(
+            "expected 2 generators, found {}",
+            generators.len()
+        ))),
+    }
+}
+
+impl Solver for Day15 {
+    type Input = Lines<Generator>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn solve_part1(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part1> {
+        let (a, b) = pair(input)?;
+        Ok(a.zip(b)
+            .take(40_000_000)
+            .filter(|(a, b)| *a & 0x0000ffff == *b & 0x0000ffff)
+            .count())
+    }
+
+    fn solve_part2(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part2> {
+        let (a, b) = pair(input)?;
+        Ok(a.filter(|x| x % a.kind.criteria() == 0)
+            .zip(b.filter(|x| x % b.kind.criteria() == 0))
+            .take(5_000_000)
+            .filter(|(a, b)| *a & 0x0000ffff == *b & 0x0000ffff)
+            .count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_part1, solve_part2};
+
+    const SAMPLE: &str = concat!(
+        "Generator A starts with 65\n",
+        "Generator B starts with 8921",
+    );
+
+    #[test]
+    #[ignore = "slow"]
+    fn test_sample() {
+        assert_eq!(solve_part1::<Day15>(SAMPLE).unwrap(), 588);
+        assert_eq!(solve_part2::<Day15>(SAMPLE).unwrap(), 309);
+    }
+}
@@ -0,0 +1,136 @@
+//! Day 16: Permutation Promenade
+
+use std::collections::{HashMap, VecDeque};
+
+use aoc_helpers::prelude::*;
+use rematch::rematch;
+
+use crate::error::{Error, Result};
+use crate::parse::CommaSeparated;
+use crate::solver::Solver;
+
+pub struct Day16;
+
+#[derive(Clone, Copy, Debug)]
+#[rematch]
+pub enum Move {
+    #[rematch(r"s(\d+)")]
+    Spin(usize),
+    #[rematch(r"x(\d+)/(\d+)")]
+    Exchange(usize, usize),
+    #[rematch(r"p(.)/(.)")]
+    Partner(char, char),
+}
+
+impl Move {
+    pub fn eval(&self, buffer: &mut VecDeque<char>) -> Result<()> {
+        match self {
+            Move::Spin(n) => {
+                if !buffer.is_empty() {
+                    let n = n % buffer.len();
+                    buffer.rotate_right(n);
+                }
+            }
+            Move::Exchange(i, j) => {
+                if *i >= buffer.len() || *j >= buffer.len() {
+                    return Err(Error::InvalidInput(format!(
+                        "can't exchange positions {i} and {j} of {} programs",
+                        buffer.len()
+                    )));
+                }
+                buffer.swap(*i, *j)
+            }
+            Move::Partner(x, y) => {
+                let position = |p: char| {
+                    buffer
+                        .iter()
+                        .position(|c| *c == p)
+                        .ok_or(Error::MissingPartner(p))
+                };
+                let i = position(*x)?;
+                let j = position(*y)?;
+                buffer.swap(i, j);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn create_entry_position(upto_letter: char) -> VecDeque<char> {
+    let mut buffer = VecDeque::with_capacity(16);
+    for c in 'a'..=upto_letter {
+        buffer.push_back(c);
+    }
+    buffer
+}
+
+pub fn perform_dance(mut buffer: VecDeque<char>, moves: &[Move]) -> Result<VecDeque<char>> {
+    for m in moves {
+        m.eval(&mut buffer)?;
+    }
+    Ok(buffer)
+}
+
+impl Solver for Day16 {
+    type Input = CommaSeparated<Move>;
+    type Part1 = String;
+    type Part2 = String;
+
+    fn solve_part1(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part1> {
+        let buffer = create_entry_position('p');
+        Ok(perform_dance(buffer, input)?.into_iter().collect())
+    }
+
+    fn solve_part2(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part2> {
+        let mut buffer = create_entry_position('p');
+        let mut seen: HashMap<String, usize> = Default::default();
+        let mut iteration = 0;
+        while iteration < 1_000_000_000 {
+            let key: String = buffer.iter().copied().collect();
+            if let Some(already_seen) = seen.insert(key, iteration) {
+                let cycle_length = iteration - already_seen;
+                let cycles_left = (1_000_000_000 - iteration) / cycle_length;
+                iteration += cycles_left * cycle_length;
+            }
+            buffer = perform_dance(buffer, input)?;
+            iteration += 1;
+        }
+        Ok(buffer.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc_helpers::parse::Parse;
+
+    const SAMPLE: &str = "s1,x3/4,pe/b";
+
+    #[test]
+    fn test_sample() {
+        let buffer = create_entry_position('e');
+        let moves = <Day16 as Solver>::Input::parse(SAMPLE).unwrap();
+        assert_eq!(
+            perform_dance(buffer.clone(), &moves)
+                .unwrap()
+                .into_iter()
+                .collect::<String>(),
+            "baedc".to_owned()
+        );
+        assert_eq!(
+            perform_dance(perform_dance(buffer, &moves).unwrap(), &moves)
+                .unwrap()
+                .into_iter()
+                .collect::<String>(),
+            "ceadb".to_owned()
+        );
+        assert!(matches!(
+            perform_dance(create_entry_position('e'), &[Move::Partner('a', 'q')]),
+            Err(Error::MissingPartner('q'))
+        ));
+    }
+}
@@ -0,0 +1,126 @@
+//! Day 18: Duet
+
+use crate::error::{Error, Result};
+use crate::solver::Solver;
+use crate::vm::budget::{Budget, Meter, Outcome};
+use crate::vm::compiled::{Compiled, FastMachine};
+use crate::vm::duet::{Duet, Topology};
+pub use crate::vm::{Arg, Instr};
+use crate::vm::{DuetProgram, Step};
+
+pub struct Day18;
+
+/// Plays `snd` as sounds until a `rcv` with a non-zero argument recovers the last one.
+pub fn recover_frequency(instrs: &[Instr], budget: Budget) -> Result<Outcome<Option<isize>>> {
+    let program = Compiled::new(instrs);
+    let mut machine = FastMachine::default();
+    let mut meter = Meter::new(budget);
+    let mut last_played = None;
+    loop {
+        if let Some(limit) = meter.check() {
+            return Ok(Outcome::Exhausted(
+                meter.exhausted(limit, machine.state(&program)),
+            ));
+        }
+        meter.steps += 1;
+        match machine.step(&program)? {
+            Step::Ran => {}
+            Step::Sent(v) => last_played = Some(v),
+            Step::Waiting(x) if machine.eval_arg(&x) != 0 => {
+                return Ok(Outcome::Finished(last_played))
+            }
+            Step::Waiting(_) => machine.skip(),
+            Step::Halted => return Ok(Outcome::Finished(None)),
+        }
+    }
+}
+
+impl Solver for Day18 {
+    type Input = DuetProgram;
+    type Part1 = isize;
+    type Part2 = usize;
+
+    fn solve_part1(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part1> {
+        recover_frequency(input, Budget::SOLVER)?
+            .into_result()?
+            .ok_or_else(|| {
+                Error::NoSolution("the program ended without recovering a frequency".to_owned())
+            })
+    }
+
+    fn solve_part2(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part2> {
+        let mut duet = Duet::copies(input, 2, &Topology::Pairwise)?;
+        duet.run_with(Budget::SOLVER)?.into_result()?;
+        Ok(duet.programs[1].sent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Lines;
+    use crate::solver::{solve_part1, solve_part2};
+    use crate::vm::duet::Reason;
+    use aoc_helpers::scaffold::Parse;
+
+    const SAMPLE1: &str = concat!(
+        "set a 1\n",
+        "add a 2\n",
+        "mul a a\n",
+        "mod a 5\n",
+        "snd a\n",
+        "set a 0\n",
+        "rcv a\n",
+        "jgz a -1\n",
+        "set a 1\n",
+        "jgz a -2\n",
+    );
+
+    const SAMPLE2: &str =
+        concat!("snd 1\n", "snd 2\n", "snd p\n", "rcv a\n", "rcv b\n", "rcv c\n", "rcv d\n",);
+
+    #[test]
+    fn test_sample() {
+        assert_eq!(solve_part1::<Day18>(SAMPLE1).unwrap(), 4);
+        assert_eq!(solve_part2::<Day18>(SAMPLE2).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(
+            solve_part1::<Day18>("set 1 2").unwrap_err().to_string(),
+            "Instruction set 1 2 needs a register, not a constant"
+        );
+        assert!(matches!(
+            solve_part1::<Day18>("snd 1\nset a 0"),
+            Err(Error::NoSolution(_))
+        ));
+        assert_eq!(
+            solve_part2::<Day18>("snd 1\nsub a 1")
+                .unwrap_err()
+                .to_string(),
+            "Parse error at line 2: sub is not part of the Duet dialect"
+        );
+    }
+
+    #[test]
+    fn test_budget() {
+        let program = Lines::<Instr>::parse("snd 1\njgz 1 -1").unwrap();
+        let Outcome::Exhausted(exhausted) = recover_frequency(&program, Budget::steps(5)).unwrap()
+        else {
+            panic!("the program never recovers a frequency");
+        };
+        assert_eq!((exhausted.steps, exhausted.state.pc), (5, 1));
+        let mut duet = Duet::copies(&program, 2, &Topology::Pairwise).unwrap();
+        let Outcome::Exhausted(exhausted) = duet.run_with(Budget::steps(5)).unwrap() else {
+            panic!("the programs never stop sending");
+        };
+        // program 0 never gives program 1 a turn
+        assert_eq!(exhausted.state.reason(), Reason::Interrupted);
+        assert_eq!(exhausted.state.programs[1].pc, 0);
+    }
+}
@@ -0,0 +1,118 @@
+//! Day 19: A Series of Tubes
+
+use aoc_helpers::{prelude::*, tile_map::TileMap};
+
+use crate::error::{Error, Result};
+use crate::parse::Grid;
+use crate::solver::Solver;
+
+pub struct Day19;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    Empty,
+    Horizontal,
+    Vertical,
+    Both,
+    Letter(char),
+}
+
+impl TryFrom<char> for Tile {
+    type Error = anyhow::Error;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            ' ' => Ok(Self::Empty),
+            '-' => Ok(Self::Horizontal),
+            '|' => Ok(Self::Vertical),
+            '+' => Ok(Self::Both),
+            'A'..='Z' | 'a'..='z' => Ok(Self::Letter(value)),
+            _ => Err(anyhow::anyhow!("Can't parse {:?}", value)),
+        }
+    }
+}
+
+pub fn solve_both(tiles: &Vec<Vec<Tile>>) -> Result<(String, usize)> {
+    let map = TileMap::from(tiles);
+    let mut letters = String::new();
+    let mut steps = 0;
+    let mut row = 0isize;
+    let (mut drow, mut dcol) = (1isize, 0isize);
+
+    let mut col = match tiles
+        .first()
+        .and_then(|row| row.iter().position(|t| *t != Tile::Empty))
+    {
+        Some(i) if tiles[0][i] == Tile::Vertical => i as isize,
+        Some(i) => {
+            return Err(Error::InvalidInput(format!(
+                "the path must start with '|', not {:?}",
+                tiles[0][i]
+            )))
+        }
+        None => {
+            return Err(Error::InvalidInput(
+                "there is no path on the first line".to_owned(),
+            ))
+        }
+    };
+
+    loop {
+        match map.get(row, col) {
+            Some(Tile::Both) => {
+                std::mem::swap(&mut drow, &mut dcol);
+                if matches!(map.get(row + drow, col + dcol), None | Some(Tile::Empty)) {
+                    drow *= -1;
+                    dcol *= -1;
+                }
+            }
+            Some(Tile::Horizontal | Tile::Vertical) => {}
+            Some(Tile::Letter(c)) => letters.push(c),
+            Some(Tile::Empty) | None => break,
+        }
+        row += drow;
+        col += dcol;
+        steps += 1;
+    }
+
+    Ok((letters, steps))
+}
+
+impl Solver for Day19 {
+    type Input = Grid<Tile>;
+    type Part1 = String;
+    type Part2 = usize;
+
+    fn solve_part1(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part1> {
+        Ok(solve_both(input)?.0)
+    }
+
+    fn solve_part2(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part2> {
+        Ok(solve_both(input)?.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve_part1, solve_part2};
+
+    const SAMPLE: &str = concat!(
+        "        |          \n",
+        "        |  +--+    \n",
+        "        A  |  C    \n",
+        "    F---|----E|--+ \n",
+        "        |  |  |  D \n",
+        "        +B-+  +--+ \n",
+    );
+
+    #[test]
+    fn test_sample() {
+        assert_eq!(solve_part1::<Day19>(SAMPLE).unwrap(), "ABCDEF".to_owned());
+        assert_eq!(solve_part2::<Day19>(SAMPLE).unwrap(), 38);
+    }
+}
@@ -0,0 +1,69 @@
+//! Day 23: Coprocessor Conflagration
+
+use crate::error::{Error, Result};
+use crate::solver::Solver;
+use crate::vm::budget::{Budget, Meter};
+use crate::vm::compiled::{Compiled, FastMachine, Op};
+use crate::vm::optimize::CompositeLoop;
+use crate::vm::CoprocessorProgram;
+pub use crate::vm::{Arg, Instr};
+
+pub struct Day23;
+
+impl Solver for Day23 {
+    type Input = CoprocessorProgram;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn solve_part1(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part1> {
+        let program = Compiled::new(input);
+        let mut machine = FastMachine::default();
+        let mut meter = Meter::new(Budget::SOLVER);
+        let mut mul_calls = 0;
+        while let Some(op) = machine.current(&program) {
+            if let Some(limit) = meter.check() {
+                let exhausted = meter.exhausted(limit, machine.state(&program));
+                return Err(Error::BudgetExhausted(exhausted.to_string()));
+            }
+            if let Op::Mul(..) = op {
+                mul_calls += 1;
+            }
+            machine.step(&program)?;
+            meter.steps += 1;
+        }
+        Ok(mul_calls)
+    }
+
+    fn solve_part2(
+        input: &<Self::Input as aoc_helpers::scaffold::Parse>::Parsed,
+    ) -> Result<Self::Part2> {
+        if CompositeLoop::find_all(input).is_empty() {
+            return Err(Error::NoSolution(
+                "the program has no loop the optimizer can speed up".to_owned(),
+            ));
+        }
+        let mut machine = FastMachine::default();
+        machine.set('a', 1);
+        machine
+            .run_with(&Compiled::optimized(input), Budget::SOLVER)?
+            .into_result()?;
+        usize::try_from(machine.get('h'))
+            .map_err(|_| Error::NoSolution(format!("h ended up as {}", machine.get('h'))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::solve_part2;
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(
+            solve_part2::<Day23>(include_str!("../../inputs/day23.txt")).unwrap(),
+            909
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use aoc_helpers::prelude::anyhow;
+
+use crate::input::InputError;
+
+/// Everything that can go wrong while reading, parsing or solving a day.
+#[derive(Debug)]
+pub enum Error {
+    Input(InputError),
+    Write(PathBuf, std::io::Error),
+    /// `line` and `column` are 1-based and filled in when known.
+    Parse {
+        line: Option<usize>,
+        column: Option<usize>,
+        message: String,
+    },
+    /// Any other error, raised while parsing the given position.
+    At {
+        line: Option<usize>,
+        column: Option<usize>,
+        source: Box<Error>,
+    },
+    /// The input parsed but doesn't describe a valid puzzle.
+    InvalidInput(String),
+    /// The solver finished without finding an answer.
+    NoSolution(String),
+    /// A program ran out of steps or time before it halted.
+    BudgetExhausted(String),
+    NotARegister(String),
+    /// A result that doesn't fit a register, with checked arithmetic.
+    Overflow {
+        pc: usize,
+        instr: String,
+        expression: String,
+    },
+    /// A `mod` by zero.
+    DivideByZero {
+        pc: usize,
+        instr: String,
+    },
+    MissingPartner(char),
+    MissingRule(String),
+    UnknownState(char),
+    DuplicateParent {
+        program: String,
+        first_parent: String,
+        second_parent: String,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    pub fn parse<M: Display>(line: Option<usize>, column: Option<usize>, message: M) -> Self {
+        Self::Parse {
+            line,
+            column,
+            message: message.to_string(),
+        }
+    }
+
+    /// Adds line context to an error raised while parsing a single line.
+    pub fn at_line(self, line: usize) -> Self {
+        match self {
+            Self::Parse {
+                line: None,
+                column,
+                message,
+            } => Self::Parse {
+                line: Some(line),
+                column,
+                message,
+            },
+            Self::At {
+                line: None,
+                column,
+                source,
+            } => Self::At {
+                line: Some(line),
+                column,
+                source,
+            },
+            Self::Parse { .. } | Self::At { .. } => self,
+            other => Self::At {
+                line: Some(line),
+                column: None,
+                source: Box::new(other),
+            },
+        }
+    }
+
+    /// Adds column context to an error raised while parsing a single item of a line.
+    pub fn at_column(self, column: usize) -> Self {
+        match self {
+            Self::Parse {
+                line,
+                column: None,
+                message,
+            } => Self::Parse {
+                line,
+                column: Some(column),
+                message,
+            },
+            Self::At {
+                line,
+                column: None,
+                source,
+            } => Self::At {
+                line,
+                column: Some(column),
+                source,
+            },
+            Self::Parse { .. } | Self::At { .. } => self,
+            other => Self::At {
+                line: None,
+                column: Some(column),
+                source: Box::new(other),
+            },
+        }
+    }
+
+    /// The error without the position it was raised at.
+    pub fn inner(&self) -> &Error {
+        match self {
+            Self::At { source, .. } => source.inner(),
+            other => other,
+        }
+    }
+}
+
+fn write_position(
+    f: &mut std::fmt::Formatter<'_>,
+    line: &Option<usize>,
+    column: &Option<usize>,
+) -> std::fmt::Result {
+    write!(f, "Parse error")?;
+    match (line, column) {
+        (Some(line), Some(column)) => write!(f, " at line {line}, column {column}"),
+        (Some(line), None) => write!(f, " at line {line}"),
+        (None, Some(column)) => write!(f, " at column {column}"),
+        (None, None) => Ok(()),
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Input(e) => write!(f, "{e}"),
+            Error::Write(path, e) => write!(f, "Can't write {}: {e}", path.display()),
+            Error::Parse {
+                line,
+                column,
+                message,
+            } => {
+                write_position(f, line, column)?;
+                write!(f, ": {message}")
+            }
+            Error::At {
+                line,
+                column,
+                source,
+            } => {
+                write_position(f, line, column)?;
+                write!(f, ": {source}")
+            }
+            Error::InvalidInput(message) => write!(f, "Invalid input: {message}"),
+            Error::NoSolution(message) => write!(f, "No solution: {message}"),
+            Error::BudgetExhausted(message) => write!(f, "Budget exhausted: {message}"),
+            Error::NotARegister(instr) => {
+                write!(f, "Instruction {instr} needs a register, not a constant")
+            }
+            Error::Overflow {
+                pc,
+                instr,
+                expression,
+            } => write!(
+                f,
+                "Overflow at instruction {pc} ({instr}): {expression} doesn't fit 64 bits"
+            ),
+            Error::DivideByZero { pc, instr } => {
+                write!(f, "Division by zero at instruction {pc} ({instr})")
+            }
+            Error::MissingPartner(c) => write!(f, "There is no program {c:?} to partner with"),
+            Error::MissingRule(pattern) => write!(f, "No rule matches pattern {pattern}"),
+            Error::UnknownState(state) => write!(f, "Unknown state {state:?}"),
+            Error::DuplicateParent {
+                program,
+                first_parent,
+                second_parent,
+            } => write!(
+                f,
+                "Program {program} is held by both {first_parent} and {second_parent}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::At { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<InputError> for Error {
+    fn from(e: InputError) -> Self {
+        Self::Input(e)
+    }
+}
+
+/// serde_json appends the position to its messages, it's kept in the fields instead.
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        let message = e.to_string();
+        let message = match message.rfind(" at line ") {
+            Some(idx) => message[..idx].to_owned(),
+            None => message,
+        };
+        let position = |n: usize| Some(n).filter(|n| *n > 0);
+        Error::parse(position(e.line()), position(e.column()), message)
+    }
+}
+
+/// `aoc_helpers` parsers return `anyhow` errors, ours are carried inside them.
+impl From<anyhow::Error> for Error {
+    fn from(e: anyhow::Error) -> Self {
+        e.downcast::<Error>()
+            .unwrap_or_else(|e| Error::parse(None, None, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Error::parse(Some(3), Some(7), "bad").to_string(),
+            "Parse error at line 3, column 7: bad"
+        );
+        assert_eq!(
+            Error::parse(None, Some(7), "bad").at_line(2).to_string(),
+            "Parse error at line 2, column 7: bad"
+        );
+        assert_eq!(
+            Error::MissingPartner('q').at_line(5).to_string(),
+            "Parse error at line 5: There is no program 'q' to partner with"
+        );
+    }
+
+    #[test]
+    fn test_position_keeps_the_error() {
+        let e = Error::UnknownState('Z').at_column(4).at_line(2).at_line(9);
+        assert!(matches!(
+            e,
+            Error::At {
+                line: Some(2),
+                column: Some(4),
+                ..
+            }
+        ));
+        assert!(matches!(e.inner(), Error::UnknownState('Z')));
+        assert_eq!(
+            e.to_string(),
+            "Parse error at line 2, column 4: Unknown state 'Z'"
+        );
+        assert!(matches!(
+            Error::parse(None, None, "bad").at_line(1),
+            Error::Parse { line: Some(1), .. }
+        ));
+    }
+
+    #[test]
+    fn test_from_anyhow() {
+        let e = Error::from(anyhow::Error::from(Error::UnknownState('Z')));
+        assert!(matches!(e, Error::UnknownState('Z')));
+        let e = Error::from(anyhow::anyhow!("nope"));
+        assert!(matches!(e, Error::Parse { line: None, .. }));
+    }
+}
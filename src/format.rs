@@ -0,0 +1,51 @@
+//! Formatting of measurements, for people and for JSON.
+use std::time::Duration;
+
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// Three significant digits in the most fitting unit.
+pub fn format_duration(d: Duration) -> String {
+    let nanos = d.as_nanos() as f64;
+    let (value, unit) = if nanos < 1e3 {
+        (nanos, "ns")
+    } else if nanos < 1e6 {
+        (nanos / 1e3, "µs")
+    } else if nanos < 1e9 {
+        (nanos / 1e6, "ms")
+    } else {
+        (nanos / 1e9, "s")
+    };
+    let decimals = if value < 10.0 {
+        2
+    } else if value < 100.0 {
+        1
+    } else {
+        0
+    };
+    format!("{value:.decimals$}{unit}")
+}
+
+/// `#[serde(with = "nanos")]` writes a `Duration` as a whole number of nanoseconds.
+pub mod nanos {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(d: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(d.as_nanos() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_nanos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(999)), "999ns");
+        assert_eq!(format_duration(Duration::from_micros(12345)), "12.3ms");
+        assert_eq!(format_duration(Duration::from_secs(150)), "150s");
+    }
+}
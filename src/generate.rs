@@ -0,0 +1,789 @@
+//! Seeded random puzzle inputs for every day, to test the solvers on more than the checked-in inputs.
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::runner::{self, Part};
+
+/// SplitMix64, small and good enough to make inputs, the same seed always gives the same input.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..n`, `n` must not be 0.
+    pub fn below(&mut self, n: u64) -> u64 {
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+
+    pub fn index(&mut self, len: usize) -> usize {
+        self.below(len as u64) as usize
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let width = range.end().abs_diff(*range.start()) + 1;
+        range.start().wrapping_add(self.below(width) as i64)
+    }
+
+    pub fn usize(&mut self, range: RangeInclusive<usize>) -> usize {
+        range.start() + self.index(range.end() - range.start() + 1)
+    }
+
+    /// True `percent` out of a hundred times.
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for idx in (1..items.len()).rev() {
+            items.swap(idx, self.index(idx + 1));
+        }
+    }
+
+    pub fn word(&mut self, len: RangeInclusive<usize>) -> String {
+        let len = self.usize(len);
+        (0..len)
+            .map(|_| (b'a' + self.below(26) as u8) as char)
+            .collect()
+    }
+}
+
+/// A random input for `day`, `None` if there is no such day.
+pub fn generate(day: usize, seed: u64) -> Option<String> {
+    let rng = &mut Rng::new(seed);
+    Some(match day {
+        1 => day01(rng),
+        2 => day02(rng),
+        3 => day03(rng),
+        4 => day04(rng),
+        5 => day05(rng),
+        6 => day06(rng),
+        7 => day07(rng).0,
+        8 => day08(rng),
+        9 => day09(rng),
+        10 => day10(rng),
+        11 => day11(rng),
+        12 => day12(rng),
+        13 => day13(rng),
+        14 => day14(rng),
+        15 => day15(rng),
+        16 => day16(rng),
+        17 => day17(rng),
+        18 => day18(rng),
+        19 => day19(rng),
+        20 => day20(rng),
+        21 => day21(rng),
+        22 => day22(rng),
+        23 => day23(rng),
+        24 => day24(rng),
+        25 => day25(rng),
+        _ => return None,
+    })
+}
+
+/// Writes `dayNN.txt` files that can be run with `--inputs dir`.
+pub fn write_inputs(days: RangeInclusive<usize>, seed: u64, dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir).map_err(|e| Error::Write(dir.to_owned(), e))?;
+    for day in days {
+        let input = generate(day, seed)
+            .ok_or_else(|| Error::InvalidInput(format!("there is no day {day}")))?;
+        let path = dir.join(format!("day{day:02}.txt"));
+        std::fs::write(&path, input).map_err(|e| Error::Write(path, e))?;
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Failure {
+    pub day: usize,
+    pub seed: u64,
+    pub part: Part,
+    pub message: String,
+}
+
+/// Runs the parts on an input generated from each seed, collecting errors and panics.
+pub fn stress(day: usize, seeds: std::ops::Range<u64>, parts: &[Part]) -> Result<Vec<Failure>> {
+    let solver =
+        runner::day(day).ok_or_else(|| Error::InvalidInput(format!("there is no day {day}")))?;
+    let mut failures = Vec::new();
+    for seed in seeds {
+        let input = generate(day, seed).expect("days are checked above");
+        for part in parts {
+            let message = match std::panic::catch_unwind(|| solver.run(&input, *part)) {
+                Ok(Ok(_)) => continue,
+                Ok(Err(e)) => e.to_string(),
+                Err(panic) => match panic.downcast_ref::<&str>() {
+                    Some(s) => format!("panicked: {s}"),
+                    None => match panic.downcast_ref::<String>() {
+                        Some(s) => format!("panicked: {s}"),
+                        None => "panicked".to_owned(),
+                    },
+                },
+            };
+            failures.push(Failure {
+                day,
+                seed,
+                part: *part,
+                message,
+            });
+        }
+    }
+    Ok(failures)
+}
+
+fn join<T: ToString>(items: impl IntoIterator<Item = T>, separator: &str) -> String {
+    items
+        .into_iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn day01(rng: &mut Rng) -> String {
+    let len = rng.usize(1000..=3000);
+    (0..len)
+        .map(|_| char::from_digit(rng.below(10) as u32, 10).unwrap())
+        .collect()
+}
+
+/// Every row gets at least one evenly divisible pair.
+fn day02(rng: &mut Rng) -> String {
+    let mut rows = Vec::new();
+    for _ in 0..16 {
+        let divisor = rng.usize(2..=500);
+        let mut row = vec![divisor, divisor * rng.usize(2..=9)];
+        while row.len() < 16 {
+            row.push(rng.usize(2..=5000));
+        }
+        rng.shuffle(&mut row);
+        rows.push(join(row, "\t"));
+    }
+    rows.join("\n")
+}
+
+fn day03(rng: &mut Rng) -> String {
+    rng.usize(1..=1_000_000).to_string()
+}
+
+/// Some passphrases repeat a word, some contain anagrams.
+fn day04(rng: &mut Rng) -> String {
+    let mut lines = Vec::new();
+    for _ in 0..512 {
+        let mut words: Vec<String> = (0..rng.usize(4..=12)).map(|_| rng.word(2..=7)).collect();
+        if rng.chance(20) {
+            let word = rng.choose(&words).clone();
+            words.push(word);
+        }
+        if rng.chance(30) {
+            let mut letters: Vec<char> = rng.choose(&words).chars().collect();
+            rng.shuffle(&mut letters);
+            words.push(letters.into_iter().collect());
+        }
+        rng.shuffle(&mut words);
+        lines.push(words.join(" "));
+    }
+    lines.join("\n")
+}
+
+/// Like the real inputs the jumps never go in front of the first offset.
+fn day05(rng: &mut Rng) -> String {
+    let len = rng.usize(500..=1100) as i64;
+    join((0..len).map(|idx| rng.range(-idx..=2)), "\n")
+}
+
+fn day06(rng: &mut Rng) -> String {
+    join((0..16).map(|_| rng.below(16)), "\t")
+}
+
+/// A tower where exactly one program has the wrong weight, along with the weight it should have.
+fn day07(rng: &mut Rng) -> (String, usize) {
+    struct Node {
+        name: String,
+        weight: usize,
+        subs: Vec<usize>,
+    }
+
+    fn node(nodes: &mut Vec<Node>, names: &mut HashSet<String>, rng: &mut Rng) -> usize {
+        let name = loop {
+            let name = rng.word(4..=7);
+            if names.insert(name.clone()) {
+                break name;
+            }
+        };
+        nodes.push(Node {
+            name,
+            weight: 0,
+            subs: Vec::new(),
+        });
+        nodes.len() - 1
+    }
+
+    /// Builds a balanced tower weighing exactly `total`.
+    fn tower(
+        nodes: &mut Vec<Node>,
+        names: &mut HashSet<String>,
+        rng: &mut Rng,
+        total: usize,
+        depth: usize,
+    ) -> usize {
+        let idx = node(nodes, names, rng);
+        let weight = rng.usize(1..=99.min(total));
+        let rest = total - weight;
+        let counts: Vec<usize> = (3..=7).filter(|k| rest.is_multiple_of(*k)).collect();
+        if depth == 0 || rest / 7 < 10 || counts.is_empty() {
+            nodes[idx].weight = total;
+            return idx;
+        }
+        let count = *rng.choose(&counts);
+        nodes[idx].weight = weight;
+        for _ in 0..count {
+            let sub = tower(nodes, names, rng, rest / count, depth - 1);
+            nodes[idx].subs.push(sub);
+        }
+        idx
+    }
+
+    let mut nodes = Vec::new();
+    let mut names = HashSet::new();
+    let root = node(&mut nodes, &mut names, rng);
+    nodes[root].weight = rng.usize(20..=99);
+    let subs = rng.usize(3..=7);
+    let total = rng.usize(5_000..=60_000);
+    for _ in 0..subs {
+        let sub = tower(&mut nodes, &mut names, rng, total, 5);
+        nodes[root].subs.push(sub);
+    }
+
+    // only a program with at least two siblings can be told apart from them
+    let candidates: Vec<usize> = nodes
+        .iter()
+        .filter(|node| node.subs.len() >= 3)
+        .flat_map(|node| node.subs.iter().copied())
+        .collect();
+    let unbalanced = *rng.choose(&candidates);
+    let expected = nodes[unbalanced].weight;
+    let delta = rng.usize(1..=9);
+    nodes[unbalanced].weight = if rng.chance(50) || expected <= delta {
+        expected + delta
+    } else {
+        expected - delta
+    };
+
+    let mut order: Vec<usize> = (0..nodes.len()).collect();
+    rng.shuffle(&mut order);
+    let lines = order.into_iter().map(|idx| {
+        let node = &nodes[idx];
+        let mut line = format!("{} ({})", node.name, node.weight);
+        if !node.subs.is_empty() {
+            let subs = node.subs.iter().map(|sub| nodes[*sub].name.as_str());
+            write!(line, " -> {}", join(subs, ", ")).unwrap();
+        }
+        line
+    });
+    (join(lines, "\n"), expected)
+}
+
+fn day08(rng: &mut Rng) -> String {
+    let registers: Vec<String> = (0..rng.usize(10..=30)).map(|_| rng.word(1..=3)).collect();
+    let lines = (0..1000).map(|_| {
+        format!(
+            "{} {} {} if {} {} {}",
+            rng.choose(&registers),
+            rng.choose(&["inc", "dec"]),
+            rng.range(-1000..=1000),
+            rng.choose(&registers),
+            rng.choose(&["==", "!=", ">=", "<=", ">", "<"]),
+            rng.range(-10..=10),
+        )
+    });
+    join(lines, "\n")
+}
+
+fn day09(rng: &mut Rng) -> String {
+    fn garbage(rng: &mut Rng, out: &mut String) {
+        out.push('<');
+        for _ in 0..rng.usize(0..=12) {
+            if rng.chance(15) {
+                out.push('!');
+                out.push(*rng.choose(&['!', '>', '<', 'a', '{', '}', '"']));
+            } else {
+                out.push(*rng.choose(&['a', 'e', 'i', 'o', 'u', '{', '}', '<', ',', '\'', '"']));
+            }
+        }
+        out.push('>');
+    }
+
+    fn group(rng: &mut Rng, depth: usize, out: &mut String) {
+        out.push('{');
+        let children = if depth < 9 { rng.usize(0..=4) } else { 0 };
+        for idx in 0..children {
+            if idx > 0 {
+                out.push(',');
+            }
+            if rng.chance(30) {
+                garbage(rng, out);
+            } else {
+                group(rng, depth + 1, out);
+            }
+        }
+        out.push('}');
+    }
+
+    let mut out = String::new();
+    group(rng, 0, &mut out);
+    out
+}
+
+fn day10(rng: &mut Rng) -> String {
+    join((0..16).map(|_| rng.below(256)), ",")
+}
+
+/// Directions are skewed so that the child actually gets somewhere.
+fn day11(rng: &mut Rng) -> String {
+    let directions = ["n", "ne", "se", "s", "sw", "nw"];
+    let favourite = rng.index(directions.len());
+    let steps = (0..rng.usize(5000..=9000)).map(|_| {
+        if rng.chance(25) {
+            directions[favourite]
+        } else {
+            *rng.choose(&directions)
+        }
+    });
+    join(steps, ",")
+}
+
+/// Every pipe is listed from both ends, programs without pipes are connected to themselves.
+fn day12(rng: &mut Rng) -> String {
+    let count = rng.usize(200..=2000);
+    let mut neighbours = vec![BTreeSet::new(); count];
+    for _ in 0..rng.usize(count / 2..=count) {
+        let (a, b) = (rng.index(count), rng.index(count));
+        neighbours[a].insert(b);
+        neighbours[b].insert(a);
+    }
+    let lines = neighbours.into_iter().enumerate().map(|(idx, mut others)| {
+        if others.is_empty() {
+            others.insert(idx);
+        }
+        format!("{idx} <-> {}", join(others, ", "))
+    });
+    join(lines, "\n")
+}
+
+/// Ranges are picked so that some delay gets through without being caught.
+fn day13(rng: &mut Rng) -> String {
+    let delay = rng.usize(0..=4_000_000);
+    let mut lines = Vec::new();
+    let mut depth = 0;
+    while depth < 90 {
+        let range = loop {
+            let range = rng.usize(2..=20);
+            if !(depth + delay).is_multiple_of(range * 2 - 2) {
+                break range;
+            }
+        };
+        lines.push(format!("{depth}: {range}"));
+        depth += rng.usize(1..=3);
+    }
+    lines.join("\n")
+}
+
+fn day14(rng: &mut Rng) -> String {
+    rng.word(8..=8)
+}
+
+fn day15(rng: &mut Rng) -> String {
+    format!(
+        "Generator A starts with {}\nGenerator B starts with {}",
+        rng.usize(1..=1000),
+        rng.usize(1..=1000)
+    )
+}
+
+fn day16(rng: &mut Rng) -> String {
+    let programs: Vec<char> = ('a'..='p').collect();
+    let moves = (0..rng.usize(5000..=10000)).map(|_| match rng.below(3) {
+        0 => format!("s{}", rng.usize(1..=15)),
+        1 => format!("x{}/{}", rng.below(16), rng.below(16)),
+        _ => {
+            let a = *rng.choose(&programs);
+            let b = loop {
+                let b = *rng.choose(&programs);
+                if b != a {
+                    break b;
+                }
+            };
+            format!("p{a}/{b}")
+        }
+    });
+    join(moves, ",")
+}
+
+fn day17(rng: &mut Rng) -> String {
+    rng.usize(1..=1000).to_string()
+}
+
+/// The real program with a different seed and amount of numbers to sort.
+fn day18(rng: &mut Rng) -> String {
+    let count = rng.usize(20..=127);
+    format!(
+        "set i 31
+set a 1
+mul p 17
+jgz p p
+mul a 2
+add i -1
+jgz i -2
+add a -1
+set i {count}
+set p {}
+mul p 8505
+mod p a
+mul p 129749
+add p 12345
+mod p a
+set b p
+mod b 10000
+snd b
+add i -1
+jgz i -9
+jgz a 3
+rcv b
+jgz b -1
+set f 0
+set i {}
+rcv a
+rcv b
+set p a
+mul p -1
+add p b
+jgz p 4
+snd a
+set a b
+jgz 1 3
+snd b
+set f 1
+add i -1
+jgz i -11
+snd a
+jgz f -16
+jgz a -19",
+        rng.usize(1..=1000),
+        count - 1
+    )
+}
+
+/// A path zigzagging to the right so it never runs into itself, with letters along the way.
+fn day19(rng: &mut Rng) -> String {
+    let (height, width) = (rng.usize(20..=60), rng.usize(40..=120));
+    let mut grid = vec![vec![' '; width]; height];
+    let mut letters = ('A'..='Z').collect::<Vec<_>>();
+    rng.shuffle(&mut letters);
+    let mut letters = letters.into_iter();
+
+    let (mut row, mut col) = (0, rng.usize(0..=width / 4));
+    grid[row][col] = '|';
+    let mut down = true;
+    loop {
+        let target = if down {
+            rng.usize(row + 2..=height - 1)
+        } else {
+            rng.usize(0..=row - 2)
+        };
+        while row != target {
+            row = if down { row + 1 } else { row - 1 };
+            grid[row][col] = '|';
+        }
+        let next_col = col + rng.usize(2..=10);
+        if next_col >= width {
+            grid[row][col] = letters.next().unwrap_or('|');
+            break;
+        }
+        grid[row][col] = '+';
+        grid[row][col + 1..next_col].fill('-');
+        col = next_col;
+        grid[row][col] = '+';
+        down = !down;
+    }
+
+    // letters replace straight pieces, but not the one the path starts on
+    let mut straight: Vec<(usize, usize)> = (1..height)
+        .flat_map(|row| (0..width).map(move |col| (row, col)))
+        .filter(|(row, col)| matches!(grid[*row][*col], '|' | '-'))
+        .collect();
+    rng.shuffle(&mut straight);
+    let count = rng.usize(3..=12);
+    for ((row, col), letter) in straight.into_iter().take(count).zip(letters) {
+        grid[row][col] = letter;
+    }
+    join(grid.into_iter().map(String::from_iter), "\n")
+}
+
+/// Some particles are aimed at each other so that they collide.
+fn day20(rng: &mut Rng) -> String {
+    let mut particles = Vec::new();
+    while particles.len() < 1000 {
+        if rng.chance(10) {
+            let time = rng.range(1..=40);
+            let meeting: [i64; 3] = std::array::from_fn(|_| rng.range(-2000..=2000));
+            for _ in 0..rng.usize(2..=4) {
+                let v: [i64; 3] = std::array::from_fn(|_| rng.range(-100..=100));
+                let a: [i64; 3] = std::array::from_fn(|_| rng.range(-10..=10));
+                let p: [i64; 3] = std::array::from_fn(|i| {
+                    meeting[i] - time * v[i] - a[i] * time * (time + 1) / 2
+                });
+                particles.push([p, v, a]);
+            }
+        } else {
+            particles.push([
+                std::array::from_fn(|_| rng.range(-3000..=3000)),
+                std::array::from_fn(|_| rng.range(-150..=150)),
+                std::array::from_fn(|_| rng.range(-20..=20)),
+            ]);
+        }
+    }
+    rng.shuffle(&mut particles);
+    let lines = particles.into_iter().map(|[p, v, a]| {
+        format!(
+            "p=<{},{},{}>, v=<{},{},{}>, a=<{},{},{}>",
+            p[0], p[1], p[2], v[0], v[1], v[2], a[0], a[1], a[2]
+        )
+    });
+    join(lines, "\n")
+}
+
+/// One rule for every 2x2 and 3x3 pattern up to rotation and flipping, so no image gets stuck.
+fn day21(rng: &mut Rng) -> String {
+    fn pattern(bits: usize, size: usize) -> String {
+        let rows = (0..size).map(|row| {
+            (0..size)
+                .map(|col| {
+                    if bits & (1 << (row * size + col)) != 0 {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>()
+        });
+        join(rows, "/")
+    }
+
+    fn transform(bits: usize, size: usize, f: impl Fn(usize, usize) -> (usize, usize)) -> usize {
+        let mut out = 0;
+        for row in 0..size {
+            for col in 0..size {
+                if bits & (1 << (row * size + col)) != 0 {
+                    let (row, col) = f(row, col);
+                    out |= 1 << (row * size + col);
+                }
+            }
+        }
+        out
+    }
+
+    let mut lines = Vec::new();
+    for size in [2, 3] {
+        let last = size - 1;
+        let mut seen = HashSet::new();
+        for bits in 0..1 << (size * size) {
+            if seen.contains(&bits) {
+                continue;
+            }
+            let mut variant = bits;
+            for _ in 0..4 {
+                variant = transform(variant, size, |row, col| (col, last - row));
+                seen.insert(variant);
+                seen.insert(transform(variant, size, |row, col| (row, last - col)));
+            }
+            let output = rng.below(1 << ((size + 1) * (size + 1))) as usize;
+            lines.push(format!(
+                "{} => {}",
+                pattern(bits, size),
+                pattern(output, size + 1)
+            ));
+        }
+    }
+    rng.shuffle(&mut lines);
+    lines.join("\n")
+}
+
+fn day22(rng: &mut Rng) -> String {
+    let size = rng.usize(5..=20) * 2 + 1;
+    let rows = (0..size).map(|_| {
+        (0..size)
+            .map(|_| if rng.chance(50) { '#' } else { '.' })
+            .collect::<String>()
+    });
+    join(rows, "\n")
+}
+
+/// The real program with a different starting number.
+fn day23(rng: &mut Rng) -> String {
+    format!(
+        "set b {}
+set c b
+jnz a 2
+jnz 1 5
+mul b 100
+sub b -100000
+set c b
+sub c -17000
+set f 1
+set d 2
+set e 2
+set g d
+mul g e
+sub g b
+jnz g 2
+set f 0
+sub e -1
+set g e
+sub g b
+jnz g -8
+sub d -1
+set g d
+sub g b
+jnz g -13
+jnz f 2
+sub h -1
+set g b
+sub g c
+jnz g 2
+jnz 1 3
+sub b -17
+jnz 1 -23",
+        rng.usize(57..=99)
+    )
+}
+
+/// Distinct components, a few of which fit the zero pin port.
+fn day24(rng: &mut Rng) -> String {
+    let count = rng.usize(30..=57);
+    let mut components = BTreeSet::new();
+    for _ in 0..rng.usize(1..=3) {
+        components.insert((0, rng.usize(1..=50)));
+    }
+    while components.len() < count {
+        let (a, b) = (rng.usize(0..=50), rng.usize(0..=50));
+        components.insert((a.min(b), a.max(b)));
+    }
+    let mut lines: Vec<String> = components
+        .into_iter()
+        .map(|(a, b)| {
+            if rng.chance(50) {
+                format!("{a}/{b}")
+            } else {
+                format!("{b}/{a}")
+            }
+        })
+        .collect();
+    rng.shuffle(&mut lines);
+    lines.join("\n")
+}
+
+fn day25(rng: &mut Rng) -> String {
+    let states: Vec<char> = ('A'..='Z').take(rng.usize(2..=6)).collect();
+    let mut out = format!(
+        "Begin in state A.\nPerform a diagnostic checksum after {} steps.\n",
+        rng.usize(1_000..=1_000_000)
+    );
+    for state in &states {
+        write!(out, "\nIn state {state}:\n").unwrap();
+        for value in 0..2 {
+            write!(
+                out,
+                "  If the current value is {value}:
+    - Write the value {}.
+    - Move one slot to the {}.
+    - Continue with state {}.
+",
+                rng.below(2),
+                rng.choose(&["left", "right"]),
+                rng.choose(&states)
+            )
+            .unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::days::day12::{self, Node};
+    use crate::parse::Lines;
+    use aoc_helpers::scaffold::Parse;
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(7);
+        let first: Vec<u64> = (0..5).map(|_| rng.next_u64()).collect();
+        let mut rng = Rng::new(7);
+        assert_eq!((0..5).map(|_| rng.next_u64()).collect::<Vec<_>>(), first);
+        assert!((0..1000).all(|_| (-3..=3).contains(&rng.range(-3..=3))));
+        assert_eq!(rng.range(5..=5), 5);
+        let mut items: Vec<usize> = (0..20).collect();
+        rng.shuffle(&mut items);
+        items.sort_unstable();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_reproducible() {
+        for day in 1..=25 {
+            assert_eq!(generate(day, 3), generate(day, 3));
+        }
+        assert_ne!(generate(7, 1), generate(7, 2));
+        assert_eq!(generate(26, 1), None);
+    }
+
+    #[test]
+    fn test_day07_has_one_unbalanced_program() {
+        for seed in 0..20 {
+            let (input, expected) = day07(&mut Rng::new(seed));
+            assert_eq!(
+                runner::day(7).unwrap().run(&input, Part::Two).unwrap(),
+                expected.to_string(),
+                "seed {seed}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_day12_is_symmetric() {
+        let nodes = Lines::<Node>::parse(&generate(12, 5).unwrap()).unwrap();
+        day12::check_nodes(&nodes).unwrap();
+        for node in &nodes {
+            for other in &node.vertices {
+                assert!(nodes[*other].vertices.contains(&node.idx));
+            }
+        }
+    }
+
+    #[test]
+    fn test_stress() {
+        for day in [1, 2, 4, 6, 8, 9, 10, 11, 12, 16, 19] {
+            assert_eq!(stress(day, 0..3, &Part::BOTH).unwrap(), vec![], "day {day}");
+        }
+    }
+}
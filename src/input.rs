@@ -0,0 +1,108 @@
+use std::fmt::Display;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_INPUTS_DIR: &str = "inputs";
+
+/// Where a day's puzzle input is read from at runtime.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputSource {
+    Stdin,
+    File(PathBuf),
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Missing(PathBuf),
+    Unreadable(InputSource, std::io::Error),
+    Empty(InputSource),
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::Stdin => write!(f, "stdin"),
+            InputSource::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputError::Missing(path) => write!(f, "Input file {} doesn't exist", path.display()),
+            InputError::Unreadable(source, e) => write!(f, "Can't read input from {source}: {e}"),
+            InputError::Empty(source) => write!(f, "Input from {source} is empty"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+impl InputSource {
+    /// `-` means stdin, anything else is a path.
+    pub fn from_arg(arg: &str) -> Self {
+        if arg == "-" {
+            Self::Stdin
+        } else {
+            Self::File(PathBuf::from(arg))
+        }
+    }
+
+    pub fn for_day(inputs_dir: &Path, day: usize) -> Self {
+        Self::File(inputs_dir.join(format!("day{day:02}.txt")))
+    }
+
+    pub fn read(&self) -> Result<String, InputError> {
+        let input = match self {
+            InputSource::Stdin => {
+                let mut input = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|e| InputError::Unreadable(self.clone(), e))?;
+                input
+            }
+            InputSource::File(path) => std::fs::read_to_string(path).map_err(|e| {
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    InputError::Missing(path.clone())
+                } else {
+                    InputError::Unreadable(self.clone(), e)
+                }
+            })?,
+        };
+        if input.trim().is_empty() {
+            Err(InputError::Empty(self.clone()))
+        } else {
+            Ok(input)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_arg() {
+        assert_eq!(InputSource::from_arg("-"), InputSource::Stdin);
+        assert_eq!(
+            InputSource::from_arg("foo/day01.txt"),
+            InputSource::File(PathBuf::from("foo/day01.txt"))
+        );
+        assert_eq!(
+            InputSource::for_day(Path::new("foo"), 1),
+            InputSource::File(PathBuf::from("foo/day01.txt"))
+        );
+    }
+
+    #[test]
+    fn test_read_errors() {
+        let missing = InputSource::from_arg("inputs/day00.txt");
+        assert!(matches!(missing.read(), Err(InputError::Missing(_))));
+        let dir = InputSource::from_arg("inputs");
+        assert!(matches!(dir.read(), Err(InputError::Unreadable(_, _))));
+        assert!(InputSource::for_day(Path::new(DEFAULT_INPUTS_DIR), 1)
+            .read()
+            .is_ok());
+    }
+}
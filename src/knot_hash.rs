@@ -12,7 +12,7 @@ pub fn reverse_circular<T>(slice: &mut [T], mut first: usize, mut last: usize) {
 pub fn knot_hash_list<const LIST_LENGTH: usize, const ROUNDS: usize>(
     lengths: &[usize],
 ) -> Vec<usize> {
-    let mut lst: Vec<usize> = (0..LIST_LENGTH).into_iter().collect();
+    let mut lst: Vec<usize> = (0..LIST_LENGTH).collect();
     let mut current_position = 0;
     let mut skip_size = 0;
     for _ in 0..ROUNDS {
@@ -33,7 +33,7 @@ pub fn knot_hash_list<const LIST_LENGTH: usize, const ROUNDS: usize>(
 
 pub fn dense_to_sparse(dense: &[usize]) -> Vec<usize> {
     assert!(
-        dense.len() % 16 == 0,
+        dense.len().is_multiple_of(16),
         "Dense hash lenght must be divisible by 16"
     );
     dense
@@ -1 +1,15 @@
+pub mod bench;
+pub mod days;
+pub mod error;
+pub mod format;
+pub mod generate;
+pub mod input;
 pub mod knot_hash;
+pub mod number_theory;
+pub mod parallel;
+pub mod parse;
+pub mod runner;
+pub mod solver;
+mod table;
+pub mod verify;
+pub mod vm;
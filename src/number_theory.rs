@@ -0,0 +1,193 @@
+//! Primes and modular arithmetic on `u64`.
+use std::ops::Range;
+
+/// Witnesses that make Miller–Rabin exact for every `u64`.
+const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+/// Numbers the sieve marks at a time, small enough to stay in the cache.
+const SEGMENT: u64 = 1 << 16;
+
+/// `a * b % m` without overflowing.
+///
+/// # Panics
+///
+/// If `m` is 0.
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+/// `base` to the power of `exp`, modulo `m`.
+///
+/// # Panics
+///
+/// If `m` is 0.
+pub fn mod_pow(base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut base = base % m;
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// The `x` in `0..m` with `a * x % m == 1`, `None` if `a` and `m` share a factor.
+pub fn mod_inv(a: u64, m: u64) -> Option<u64> {
+    if m == 0 {
+        return None;
+    }
+    // extended Euclid, keeping only the coefficients of `a`
+    let (mut r, mut next_r) = (m as i128, (a % m) as i128);
+    let (mut t, mut next_t) = (0i128, 1i128);
+    while next_r != 0 {
+        let q = r / next_r;
+        (r, next_r) = (next_r, r - q * next_r);
+        (t, next_t) = (next_t, t - q * next_t);
+    }
+    (r == 1).then(|| t.rem_euclid(m as i128) as u64)
+}
+
+/// Deterministic Miller–Rabin, exact for every `u64`.
+pub fn is_prime(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    if let Some(p) = WITNESSES.iter().find(|p| n.is_multiple_of(**p)) {
+        return n == *p;
+    }
+    let shift = (n - 1).trailing_zeros();
+    let odd = (n - 1) >> shift;
+    WITNESSES.iter().all(|witness| {
+        let mut x = mod_pow(*witness, odd, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        (1..shift).any(|_| {
+            x = mul_mod(x, x, n);
+            x == n - 1
+        })
+    })
+}
+
+/// The primes up to and including `limit`, with a plain sieve.
+fn primes_up_to(limit: u64) -> Vec<u64> {
+    let mut composite = vec![false; limit as usize + 1];
+    let mut primes = vec![];
+    for n in 2..=limit {
+        if composite[n as usize] {
+            continue;
+        }
+        primes.push(n);
+        for multiple in (n * n..=limit).step_by(n as usize) {
+            composite[multiple as usize] = true;
+        }
+    }
+    primes
+}
+
+/// Calls `f` with the primes in `range` in order, sieving a segment at a time.
+fn for_each_prime(range: Range<u64>, mut f: impl FnMut(u64)) {
+    let start = range.start.max(2);
+    if start >= range.end {
+        return;
+    }
+    let base = primes_up_to((range.end - 1).isqrt());
+    let mut composite = vec![false; SEGMENT as usize];
+    let mut low = start;
+    while low < range.end {
+        let high = low.saturating_add(SEGMENT).min(range.end);
+        composite.fill(false);
+        for p in base.iter().take_while(|p| **p * **p < high) {
+            let Some(first) = low.div_ceil(*p).checked_mul(*p) else {
+                continue;
+            };
+            for multiple in (first.max(p * p)..high).step_by(*p as usize) {
+                composite[(multiple - low) as usize] = true;
+            }
+        }
+        for (offset, _) in composite[..(high - low) as usize]
+            .iter()
+            .enumerate()
+            .filter(|(_, composite)| !**composite)
+        {
+            f(low + offset as u64);
+        }
+        low = high;
+    }
+}
+
+/// The primes in `range` with a segmented sieve, it needs memory for the square root of the end
+/// of the range but not for the range itself.
+pub fn primes(range: Range<u64>) -> Vec<u64> {
+    let mut primes = vec![];
+    for_each_prime(range, |p| primes.push(p));
+    primes
+}
+
+/// How many primes there are in `range`, like `primes` without keeping them.
+pub fn count_primes(range: Range<u64>) -> usize {
+    let mut count = 0;
+    for_each_prime(range, |_| count += 1);
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_prime() {
+        assert!(!is_prime(0));
+        assert!(!is_prime(1));
+        assert!(is_prime(2));
+        assert!(is_prime(3));
+        assert!(!is_prime(4));
+        assert!(is_prime(5));
+        assert!(!is_prime(9));
+        assert!(!is_prime(108105));
+        // a Carmichael number and strong pseudoprimes to the first few witnesses
+        assert!(!is_prime(561));
+        assert!(!is_prime(3_215_031_751));
+        assert!(!is_prime(3_825_123_056_546_413_051));
+        assert!(is_prime(1_000_000_007));
+        assert!(is_prime(18_446_744_073_709_551_557));
+        assert!(!is_prime(u64::MAX));
+        assert!(!is_prime(4_294_967_291 * 4_294_967_279));
+    }
+
+    #[test]
+    fn test_sieve() {
+        let sieved = primes(0..100_000);
+        let tested: Vec<u64> = (0..100_000).filter(|n| is_prime(*n)).collect();
+        assert_eq!(sieved, tested);
+        assert_eq!(sieved.len(), 9592);
+        assert_eq!(primes(0..20), [2, 3, 5, 7, 11, 13, 17, 19]);
+        assert!(primes(14..17).is_empty());
+        assert!(primes(17..17).is_empty());
+        // several segments far from zero
+        let start = 1_000_000_000_000;
+        let range = start..start + 3 * SEGMENT + 17;
+        let tested: Vec<u64> = range.clone().filter(|n| is_prime(*n)).collect();
+        assert_eq!(primes(range.clone()), tested);
+        assert_eq!(count_primes(range), tested.len());
+        assert_eq!(count_primes(0..1_000_000), 78498);
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(3, 0, 7), 1);
+        assert_eq!(mod_pow(3, 5, 1), 0);
+        assert_eq!(mod_pow(u64::MAX, u64::MAX, u64::MAX - 1), 1);
+        assert_eq!(mod_inv(3, 11), Some(4));
+        assert_eq!(mod_inv(14, 11), Some(4));
+        assert_eq!(mod_inv(6, 9), None);
+        assert_eq!(mod_inv(5, 1), Some(0));
+        let m = 18_446_744_073_709_551_557;
+        let inverse = mod_inv(123_456_789, m).unwrap();
+        assert_eq!(mul_mod(inverse, 123_456_789, m), 1);
+    }
+}
@@ -0,0 +1,141 @@
+//! Runs independent parts on a pool of worker threads, reporting results in their original order.
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::error::Result;
+use crate::runner::{Day, Part, Run};
+
+#[derive(Clone, Copy)]
+pub struct Job<'a> {
+    pub day: &'static Day,
+    pub part: Part,
+    pub input: &'a str,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// From starting the first job until the last one finished.
+    pub wall_time: Duration,
+    /// Parse and solve time of all the jobs added up.
+    pub busy_time: Duration,
+    /// Index and duration of the longest job, no amount of workers gets below that.
+    pub critical_path: Option<(usize, Duration)>,
+}
+
+/// Calls `on_result` in the order of `jobs` as soon as a job and all the ones before it are
+/// done, returning `false` from it cancels the jobs that haven't been started yet.
+pub fn run_jobs<F>(jobs: &[Job<'_>], workers: usize, mut on_result: F) -> Summary
+where
+    F: FnMut(usize, Result<Run>) -> bool,
+{
+    let start = Instant::now();
+    let next_job = AtomicUsize::new(0);
+    let cancelled = AtomicBool::new(false);
+    let mut summary = Summary::default();
+
+    std::thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..workers.clamp(1, jobs.len().max(1)) {
+            let sender = sender.clone();
+            let next_job = &next_job;
+            let cancelled = &cancelled;
+            scope.spawn(move || {
+                while !cancelled.load(Ordering::Relaxed) {
+                    let idx = next_job.fetch_add(1, Ordering::Relaxed);
+                    let Some(job) = jobs.get(idx) else {
+                        break;
+                    };
+                    let result = job.day.timed_run(job.input, job.part);
+                    if sender.send((idx, result)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        let mut finished: Vec<Option<Result<Run>>> = jobs.iter().map(|_| None).collect();
+        let mut next_to_report = 0;
+        for (idx, result) in receiver {
+            if let Ok(run) = &result {
+                let duration = run.parse_time + run.solve_time;
+                summary.busy_time += duration;
+                if summary
+                    .critical_path
+                    .is_none_or(|(_, longest)| longest < duration)
+                {
+                    summary.critical_path = Some((idx, duration));
+                }
+            }
+            finished[idx] = Some(result);
+            while !cancelled.load(Ordering::Relaxed) {
+                let Some(result) = finished.get_mut(next_to_report).and_then(Option::take) else {
+                    break;
+                };
+                if !on_result(next_to_report, result) {
+                    cancelled.store(true, Ordering::Relaxed);
+                }
+                next_to_report += 1;
+            }
+        }
+    });
+
+    summary.wall_time = start.elapsed();
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner;
+
+    #[test]
+    fn test_results_are_in_order() {
+        let inputs = ["1122", "5 9 2 8\n9 4 7 3\n3 8 6 5", "1024"];
+        let jobs: Vec<Job> = inputs
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, input)| {
+                let day = runner::day(idx + 1).unwrap();
+                Part::BOTH.map(|part| Job { day, part, input })
+            })
+            .collect();
+        let sequential: Vec<String> = jobs
+            .iter()
+            .map(|job| job.day.run(job.input, job.part).unwrap())
+            .collect();
+        for workers in [1, 2, 8] {
+            let mut answers = Vec::new();
+            let summary = run_jobs(&jobs, workers, |idx, result| {
+                assert_eq!(idx, answers.len());
+                answers.push(result.unwrap().answer.to_string());
+                true
+            });
+            assert_eq!(answers, sequential);
+            assert!(summary
+                .critical_path
+                .is_some_and(|(idx, _)| idx < jobs.len()));
+            assert!(summary.busy_time >= summary.critical_path.unwrap().1);
+        }
+    }
+
+    #[test]
+    fn test_cancel() {
+        let day = runner::day(1).unwrap();
+        let jobs = vec![
+            Job {
+                day,
+                part: Part::One,
+                input: "12",
+            };
+            20
+        ];
+        let mut reported = 0;
+        run_jobs(&jobs, 2, |_, _| {
+            reported += 1;
+            reported < 3
+        });
+        assert_eq!(reported, 3);
+    }
+}
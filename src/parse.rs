@@ -0,0 +1,178 @@
+//! Parsers like the ones in `aoc_helpers::parse` that report where parsing failed.
+use std::marker::PhantomData;
+
+use aoc_helpers::prelude::*;
+
+use crate::error::Error;
+
+/// `VecFromLines` that reports the line of the first line failing to parse.
+pub struct Lines<T>(PhantomData<T>);
+
+impl<T: Parse> Parse for Lines<T> {
+    type Parsed = Vec<T::Parsed>;
+
+    fn parse(raw_input: &str) -> anyhow::Result<Self::Parsed> {
+        raw_input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(idx, line)| T::parse(line).map_err(|e| Error::from(e).at_line(idx + 1).into()))
+            .collect()
+    }
+}
+
+/// `VecFromMultiLines` that reports the first line of the first block failing to parse.
+pub struct Blocks<T>(PhantomData<T>);
+
+impl<T: Parse> Parse for Blocks<T> {
+    type Parsed = Vec<T::Parsed>;
+
+    fn parse(raw_input: &str) -> anyhow::Result<Self::Parsed> {
+        let mut line = 1;
+        raw_input
+            .split("\n\n")
+            .filter_map(|block| {
+                let first_line = line;
+                line += block.lines().count() + 1;
+                if block.trim().is_empty() {
+                    None
+                } else {
+                    Some(T::parse(block).map_err(|e| {
+                        match Error::from(e) {
+                            // lines reported by the block's parser are relative to the block
+                            Error::Parse {
+                                line: Some(line),
+                                column,
+                                message,
+                            } => Error::parse(Some(first_line + line - 1), column, message).into(),
+                            Error::At {
+                                line: Some(line),
+                                column,
+                                source,
+                            } => Error::At {
+                                line: Some(first_line + line - 1),
+                                column,
+                                source,
+                            }
+                            .into(),
+                            e => e.at_line(first_line).into(),
+                        }
+                    }))
+                }
+            })
+            .collect()
+    }
+}
+
+/// `VecFromCommaSeparated` that reports the column of the first item failing to parse.
+pub struct CommaSeparated<T>(PhantomData<T>);
+
+impl<T: Parse> Parse for CommaSeparated<T> {
+    type Parsed = Vec<T::Parsed>;
+
+    fn parse(raw_input: &str) -> anyhow::Result<Self::Parsed> {
+        let mut column = 1;
+        raw_input
+            .trim_end()
+            .split(',')
+            .map(|item| {
+                let parsed = T::parse(item).map_err(|e| Error::from(e).at_column(column).into());
+                column += item.chars().count() + 1;
+                parsed
+            })
+            .collect()
+    }
+}
+
+/// `RowsOfChars` that reports the line and column of the first unknown character.
+pub struct Grid<T>(PhantomData<T>);
+
+impl<T: TryFrom<char>> Parse for Grid<T>
+where
+    T::Error: std::fmt::Display,
+{
+    type Parsed = Vec<Vec<T>>;
+
+    fn parse(raw_input: &str) -> anyhow::Result<Self::Parsed> {
+        raw_input
+            .lines()
+            .enumerate()
+            .map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(col, c)| {
+                        T::try_from(c)
+                            .map_err(|e| Error::parse(Some(row + 1), Some(col + 1), e).into())
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error<P: Parse>(raw_input: &str) -> String {
+        match P::parse(raw_input) {
+            Ok(_) => panic!("parsing {raw_input:?} should fail"),
+            Err(e) => Error::from(e).to_string(),
+        }
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(Lines::<usize>::parse("1\n2\n\n3\n").unwrap(), vec![1, 2, 3]);
+        assert!(parse_error::<Lines<usize>>("1\n2\nx\n").starts_with("Parse error at line 3: "));
+    }
+
+    #[test]
+    fn test_blocks() {
+        assert_eq!(
+            Blocks::<Lines<usize>>::parse("1\n2\n\n3\n").unwrap(),
+            vec![vec![1, 2], vec![3]]
+        );
+        assert!(parse_error::<Blocks<Lines<usize>>>("1\n2\n\n3\nx")
+            .starts_with("Parse error at line 5: "));
+        assert!(parse_error::<Blocks<TrimAndParse<usize>>>("1\n\n2\n\nx\n")
+            .starts_with("Parse error at line 5: "));
+    }
+
+    #[test]
+    fn test_comma_separated() {
+        assert_eq!(
+            CommaSeparated::<usize>::parse("1,20,3\n").unwrap(),
+            vec![1, 20, 3]
+        );
+        assert!(
+            parse_error::<CommaSeparated<usize>>("1,20,x").starts_with("Parse error at column 6: ")
+        );
+        assert!(parse_error::<Lines<CommaSeparated<usize>>>("1\n1,x")
+            .starts_with("Parse error at line 2, column 3: "));
+    }
+
+    #[test]
+    fn test_grid() {
+        #[derive(Debug)]
+        struct Bit(bool);
+
+        impl TryFrom<char> for Bit {
+            type Error = String;
+
+            fn try_from(c: char) -> Result<Self, Self::Error> {
+                match c {
+                    '#' => Ok(Bit(true)),
+                    '.' => Ok(Bit(false)),
+                    _ => Err(format!("Unknown tile {c:?}")),
+                }
+            }
+        }
+
+        assert!(Grid::<Bit>::parse("#.\n.#\n").unwrap()[1][1].0);
+        assert_eq!(
+            parse_error::<Grid<Bit>>("#.\n.x\n"),
+            "Parse error at line 2, column 2: Unknown tile 'x'"
+        );
+    }
+}
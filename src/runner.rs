@@ -0,0 +1,241 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use aoc_helpers::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::days::*;
+use crate::error::{Error, Result};
+use crate::format::nanos;
+use crate::input::{InputSource, DEFAULT_INPUTS_DIR};
+use crate::solver::{self, Solver};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(into = "usize", try_from = "usize")]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(&self) -> usize {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(anyhow::anyhow!("There is no part {:?}", s)),
+        }
+    }
+}
+
+impl From<Part> for usize {
+    fn from(part: Part) -> Self {
+        part.number()
+    }
+}
+
+impl TryFrom<usize> for Part {
+    type Error = String;
+
+    fn try_from(number: usize) -> Result<Self, Self::Error> {
+        match number {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            _ => Err(format!("There is no part {number}")),
+        }
+    }
+}
+
+/// Answer typed after the solver's output, so that numbers stay numbers in JSON.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Number(n as i128)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(n: isize) -> Self {
+        Answer::Number(n as i128)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+/// Answer of a single part along with how long it took to get it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Run {
+    pub answer: Answer,
+    #[serde(rename = "parse_ns", with = "nanos")]
+    pub parse_time: Duration,
+    #[serde(rename = "solve_ns", with = "nanos")]
+    pub solve_time: Duration,
+}
+
+/// Type-erased entry point for a single day's `Solver`.
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub number: usize,
+    run: fn(&str, Part) -> Result<Run>,
+}
+
+impl Day {
+    pub fn run(&self, raw_input: &str, part: Part) -> Result<String> {
+        self.timed_run(raw_input, part)
+            .map(|run| run.answer.to_string())
+    }
+
+    /// Parses the input from scratch, so parse time is measured for every part.
+    pub fn timed_run(&self, raw_input: &str, part: Part) -> Result<Run> {
+        (self.run)(raw_input, part)
+    }
+}
+
+fn run_solver<S: Solver>(raw_input: &str, part: Part) -> Result<Run>
+where
+    S::Part1: Into<Answer>,
+    S::Part2: Into<Answer>,
+{
+    let start = Instant::now();
+    let input = solver::parse::<S>(raw_input)?;
+    let parse_time = start.elapsed();
+    let start = Instant::now();
+    let answer = match part {
+        Part::One => S::solve_part1(&input)?.into(),
+        Part::Two => S::solve_part2(&input)?.into(),
+    };
+    Ok(Run {
+        answer,
+        parse_time,
+        solve_time: start.elapsed(),
+    })
+}
+
+macro_rules! days {
+    ($($number:literal => $solver:ty,)*) => {
+        pub const DAYS: [Day; 25] = [
+            $(Day { number: $number, run: run_solver::<$solver> },)*
+        ];
+    };
+}
+
+days! {
+    1 => day01::Day01,
+    2 => day02::Day02,
+    3 => day03::Day03,
+    4 => day04::Day04,
+    5 => day05::Day05,
+    6 => day06::Day06,
+    7 => day07::Day07,
+    8 => day08::Day08,
+    9 => day09::Day09,
+    10 => day10::Day10,
+    11 => day11::Day11,
+    12 => day12::Day12,
+    13 => day13::Day13,
+    14 => day14::Day14,
+    15 => day15::Day15,
+    16 => day16::Day16,
+    17 => day17::Day17,
+    18 => day18::Day18,
+    19 => day19::Day19,
+    20 => day20::Day20,
+    21 => day21::Day21,
+    22 => day22::Day22,
+    23 => day23::Day23,
+    24 => day24::Day24,
+    25 => day25::Day25,
+}
+
+pub fn day(number: usize) -> Option<&'static Day> {
+    DAYS.get(number.checked_sub(1)?)
+}
+
+/// Entry point of the per-day binaries: `dayNN [PATH|-]`, defaulting to `inputs/dayNN.txt`.
+pub fn day_main(number: usize) {
+    let day = day(number).expect("binaries are only built for existing days");
+    let source = std::env::args()
+        .nth(1)
+        .map(|arg| InputSource::from_arg(&arg))
+        .unwrap_or_else(|| InputSource::for_day(DEFAULT_INPUTS_DIR.as_ref(), number));
+    let result = source.read().map_err(Error::from).and_then(|input| {
+        for part in Part::BOTH {
+            println!("Part {}: {}", part.number(), day.run(&input, part)?);
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_days_are_in_order() {
+        for (idx, day) in DAYS.iter().enumerate() {
+            assert_eq!(day.number, idx + 1);
+        }
+        assert!(day(0).is_none());
+        assert_eq!(day(18).map(|d| d.number), Some(18));
+    }
+
+    #[test]
+    fn test_run() {
+        assert_eq!(
+            day(8)
+                .unwrap()
+                .run("b inc 5 if a > 1\na inc 1 if b < 5\n", Part::One)
+                .unwrap(),
+            "1"
+        );
+        assert!(day(13).unwrap().run("not a layer", Part::One).is_err());
+        assert_eq!(
+            day(10).unwrap().timed_run("", Part::Two).unwrap().answer,
+            Answer::Text("a2582a3a0e66e6e86e3812dcb672a272".to_owned())
+        );
+        assert_eq!(
+            day(8)
+                .unwrap()
+                .timed_run("a dec 3 if b == 0", Part::One)
+                .unwrap()
+                .answer,
+            Answer::Number(-3)
+        );
+    }
+}
@@ -0,0 +1,25 @@
+use aoc_helpers::scaffold::Parse;
+
+use crate::error::{Error, Result};
+
+/// `aoc_helpers::scaffold::Problem` for solvers that report failures instead of panicking.
+pub trait Solver {
+    type Input: Parse;
+    type Part1;
+    type Part2;
+
+    fn solve_part1(input: &<Self::Input as Parse>::Parsed) -> Result<Self::Part1>;
+    fn solve_part2(input: &<Self::Input as Parse>::Parsed) -> Result<Self::Part2>;
+}
+
+pub fn parse<S: Solver>(raw_input: &str) -> Result<<S::Input as Parse>::Parsed> {
+    S::Input::parse(raw_input.trim_end()).map_err(Error::from)
+}
+
+pub fn solve_part1<S: Solver>(raw_input: &str) -> Result<S::Part1> {
+    S::solve_part1(&parse::<S>(raw_input)?)
+}
+
+pub fn solve_part2<S: Solver>(raw_input: &str) -> Result<S::Part2> {
+    S::solve_part2(&parse::<S>(raw_input)?)
+}
@@ -0,0 +1,26 @@
+use std::fmt::Formatter;
+
+/// Writes left-aligned columns separated by two spaces, each as wide as its widest cell.
+pub(crate) fn write_table<const N: usize>(
+    f: &mut Formatter<'_>,
+    header: [&str; N],
+    rows: &[[String; N]],
+) -> std::fmt::Result {
+    let header = header.map(str::to_owned);
+    let mut widths = header.clone().map(|s| s.chars().count());
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    for row in std::iter::once(&header).chain(rows) {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        writeln!(f, "{}", line.trim_end())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,227 @@
+//! Checks answers against the known ones kept next to the inputs in `answers.txt`.
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::input::InputSource;
+use crate::runner::{self, Part};
+use crate::table::write_table;
+
+pub const ANSWERS_FILE: &str = "answers.txt";
+
+/// Known answers, one `DAY PART ANSWER` per line, `#` starts a comment.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(usize, Part), String>,
+}
+
+impl Answers {
+    pub fn parse(raw_input: &str) -> Result<Self> {
+        let mut answers = BTreeMap::new();
+        for (idx, line) in raw_input.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |message: String| Error::parse(Some(idx + 1), None, message);
+            let mut parts = line.splitn(3, char::is_whitespace);
+            let (Some(day), Some(part), Some(answer)) = (parts.next(), parts.next(), parts.next())
+            else {
+                return Err(error(format!("Expected `DAY PART ANSWER`, got {line:?}")));
+            };
+            let day = day
+                .parse::<usize>()
+                .ok()
+                .filter(|day| runner::day(*day).is_some())
+                .ok_or_else(|| error(format!("There is no day {day:?}")))?;
+            let part = part.parse::<Part>().map_err(|e| error(e.to_string()))?;
+            if answers
+                .insert((day, part), answer.trim().to_owned())
+                .is_some()
+            {
+                return Err(error(format!(
+                    "Day {day} part {} is listed twice",
+                    part.number()
+                )));
+            }
+        }
+        Ok(Self { answers })
+    }
+
+    pub fn load(inputs_dir: &Path) -> Result<Self> {
+        Self::parse(&InputSource::File(inputs_dir.join(ANSWERS_FILE)).read()?)
+    }
+
+    pub fn get(&self, day: usize, part: Part) -> Option<&str> {
+        self.answers.get(&(day, part)).map(|s| s.as_str())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    Fail {
+        expected: String,
+    },
+    /// There's no known answer to compare with.
+    Unknown,
+    Error(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Check {
+    pub day: usize,
+    pub part: Part,
+    pub answer: Option<String>,
+    pub outcome: Outcome,
+}
+
+impl Check {
+    pub fn new<E: Display>(
+        day: usize,
+        part: Part,
+        answer: Result<String, E>,
+        answers: &Answers,
+    ) -> Self {
+        let expected = answers.get(day, part);
+        let (answer, outcome) = match answer {
+            Ok(answer) => {
+                let outcome = match expected {
+                    Some(expected) if expected == answer => Outcome::Pass,
+                    Some(expected) => Outcome::Fail {
+                        expected: expected.to_owned(),
+                    },
+                    None => Outcome::Unknown,
+                };
+                (Some(answer), outcome)
+            }
+            Err(e) => (None, Outcome::Error(e.to_string())),
+        };
+        Self {
+            day,
+            part,
+            answer,
+            outcome,
+        }
+    }
+}
+
+/// Result of checking a range of days, displayed as a pass/fail table.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    pub fn count(&self, f: impl Fn(&Outcome) -> bool) -> usize {
+        self.checks.iter().filter(|check| f(&check.outcome)).count()
+    }
+
+    /// Nothing failed or errored, answers that aren't known yet don't count as failures.
+    pub fn is_success(&self) -> bool {
+        self.count(|o| matches!(o, Outcome::Fail { .. } | Outcome::Error(_))) == 0
+    }
+}
+
+pub fn verify(days: RangeInclusive<usize>, parts: &[Part], inputs_dir: &Path) -> Result<Report> {
+    let answers = Answers::load(inputs_dir)?;
+    let mut report = Report::default();
+    for number in days {
+        let day = runner::day(number)
+            .ok_or_else(|| Error::InvalidInput(format!("there is no day {number}")))?;
+        let input = InputSource::for_day(inputs_dir, number).read();
+        for part in parts {
+            let check = match &input {
+                Ok(input) => Check::new(number, *part, day.run(input, *part), &answers),
+                Err(e) => Check::new(number, *part, Err(e), &answers),
+            };
+            report.checks.push(check);
+        }
+    }
+    Ok(report)
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rows: Vec<[String; 5]> = self
+            .checks
+            .iter()
+            .map(|check| {
+                let (expected, result) = match &check.outcome {
+                    Outcome::Pass => (check.answer.clone().unwrap_or_default(), "pass".to_owned()),
+                    Outcome::Fail { expected } => (expected.clone(), "FAIL".to_owned()),
+                    Outcome::Unknown => ("?".to_owned(), "unknown".to_owned()),
+                    Outcome::Error(e) => (String::new(), format!("ERROR: {e}")),
+                };
+                [
+                    format!("{:02}", check.day),
+                    check.part.number().to_string(),
+                    expected,
+                    check.answer.clone().unwrap_or_else(|| "-".to_owned()),
+                    result,
+                ]
+            })
+            .collect();
+        write_table(f, ["Day", "Part", "Expected", "Actual", "Result"], &rows)?;
+        write!(
+            f,
+            "{} passed, {} failed, {} errors, {} unknown",
+            self.count(|o| *o == Outcome::Pass),
+            self.count(|o| matches!(o, Outcome::Fail { .. })),
+            self.count(|o| matches!(o, Outcome::Error(_))),
+            self.count(|o| *o == Outcome::Unknown),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers =
+            Answers::parse("# day part answer\n01 1 1175\n7 2 azqje # comment\n\n").unwrap();
+        assert_eq!(answers.get(1, Part::One), Some("1175"));
+        assert_eq!(answers.get(7, Part::Two), Some("azqje"));
+        assert_eq!(answers.get(1, Part::Two), None);
+        assert_eq!(
+            Answers::parse("1 1 2\n1 1 3").unwrap_err().to_string(),
+            "Parse error at line 2: Day 1 part 1 is listed twice"
+        );
+        assert!(Answers::parse("26 1 2").is_err());
+        assert!(Answers::parse("1 3 2").is_err());
+        assert!(Answers::parse("1 1").is_err());
+    }
+
+    #[test]
+    fn test_report() {
+        let answers = Answers::parse("1 1 3\n1 2 4\n2 1 18").unwrap();
+        let report = Report {
+            checks: vec![
+                Check::new(1, Part::One, Ok::<_, Error>("3".to_owned()), &answers),
+                Check::new(1, Part::Two, Ok::<_, Error>("5".to_owned()), &answers),
+                Check::new(
+                    2,
+                    Part::One,
+                    Err(Error::NoSolution("nope".to_owned())),
+                    &answers,
+                ),
+                Check::new(2, Part::Two, Ok::<_, Error>("9".to_owned()), &answers),
+            ],
+        };
+        assert!(!report.is_success());
+        assert_eq!(
+            report.to_string(),
+            "\
+Day  Part  Expected  Actual  Result
+01   1     3         3       pass
+01   2     4         5       FAIL
+02   1               -       ERROR: No solution: nope
+02   2     ?         9       unknown
+1 passed, 1 failed, 1 errors, 1 unknown"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! How `add`, `sub`, `mul` and `mod` treat results that don't fit a register and negative
+//! divisors.
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use crate::vm::Instr;
+
+/// What happens to a result that doesn't fit 64 bits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Overflow {
+    /// Fails with an error that points at the instruction.
+    #[default]
+    Checked,
+    Wrapping,
+    /// Sticks to the smallest or largest value.
+    Saturating,
+}
+
+/// The sign of the result of `mod` with negative operands.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Modulo {
+    /// Like Rust's `%`, the result has the sign of the dividend.
+    #[default]
+    Truncated,
+    /// The result is never negative.
+    Euclidean,
+}
+
+impl FromStr for Overflow {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "checked" => Ok(Overflow::Checked),
+            "wrapping" => Ok(Overflow::Wrapping),
+            "saturating" => Ok(Overflow::Saturating),
+            _ => Err(Error::InvalidInput(format!("unknown overflow mode {s:?}"))),
+        }
+    }
+}
+
+impl FromStr for Modulo {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "truncated" => Ok(Modulo::Truncated),
+            "euclidean" => Ok(Modulo::Euclidean),
+            _ => Err(Error::InvalidInput(format!("unknown modulo mode {s:?}"))),
+        }
+    }
+}
+
+/// The default fails on overflow and keeps the sign of the dividend like the puzzles expect.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Arithmetic {
+    pub overflow: Overflow,
+    pub modulo: Modulo,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Mod,
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            Operator::Add => "+",
+            Operator::Sub => "-",
+            Operator::Mul => "*",
+            Operator::Mod => "%",
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+impl Arithmetic {
+    /// `x op y`, `None` if it overflows with checked arithmetic. `y` can't be 0 for `mod`.
+    #[inline]
+    pub fn apply(self, op: Operator, x: i64, y: i64) -> Option<i64> {
+        match (op, self.overflow) {
+            (Operator::Add, Overflow::Checked) => x.checked_add(y),
+            (Operator::Add, Overflow::Wrapping) => Some(x.wrapping_add(y)),
+            (Operator::Add, Overflow::Saturating) => Some(x.saturating_add(y)),
+            (Operator::Sub, Overflow::Checked) => x.checked_sub(y),
+            (Operator::Sub, Overflow::Wrapping) => Some(x.wrapping_sub(y)),
+            (Operator::Sub, Overflow::Saturating) => Some(x.saturating_sub(y)),
+            (Operator::Mul, Overflow::Checked) => x.checked_mul(y),
+            (Operator::Mul, Overflow::Wrapping) => Some(x.wrapping_mul(y)),
+            (Operator::Mul, Overflow::Saturating) => Some(x.saturating_mul(y)),
+            // only `i64::MIN % -1` overflows in Rust, and its remainder 0 fits just fine
+            (Operator::Mod, _) => Some(match self.modulo {
+                Modulo::Truncated => x.wrapping_rem(y),
+                Modulo::Euclidean => x.wrapping_rem_euclid(y),
+            }),
+        }
+    }
+
+    /// Like `apply`, overflow is an error about the instruction at `pc`.
+    #[inline]
+    pub fn eval(self, op: Operator, x: i64, y: i64, pc: usize, instr: &Instr) -> Result<i64> {
+        self.apply(op, x, y)
+            .ok_or_else(|| overflow(op, x, y, pc, instr))
+    }
+}
+
+/// The error for `x op y` overflowing at instruction `pc`.
+pub fn overflow(op: Operator, x: i64, y: i64, pc: usize, instr: &Instr) -> Error {
+    Error::Overflow {
+        pc,
+        instr: instr.to_string(),
+        expression: format!("{x} {op} {y}"),
+    }
+}
+
+/// The error for a `mod` by zero at instruction `pc`.
+pub fn divide_by_zero(pc: usize, instr: &Instr) -> Error {
+    Error::DivideByZero {
+        pc,
+        instr: instr.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Lines;
+    use crate::vm::compiled::{Compiled, FastMachine};
+    use aoc_helpers::scaffold::Parse;
+
+    #[test]
+    fn test_apply() {
+        let mode = |overflow, modulo| Arithmetic { overflow, modulo };
+        let checked = Arithmetic::default();
+        assert_eq!(checked.apply(Operator::Add, i64::MAX, 1), None);
+        assert_eq!(checked.apply(Operator::Mul, 3, -4), Some(-12));
+        let wrapping = mode(Overflow::Wrapping, Modulo::Truncated);
+        assert_eq!(wrapping.apply(Operator::Add, i64::MAX, 1), Some(i64::MIN));
+        let saturating = mode(Overflow::Saturating, Modulo::Truncated);
+        assert_eq!(saturating.apply(Operator::Sub, i64::MIN, 1), Some(i64::MIN));
+        assert_eq!(
+            saturating.apply(Operator::Mul, i64::MAX, -2),
+            Some(i64::MIN)
+        );
+        assert_eq!(checked.apply(Operator::Mod, -7, 3), Some(-1));
+        assert_eq!(checked.apply(Operator::Mod, 7, -3), Some(1));
+        assert_eq!(checked.apply(Operator::Mod, i64::MIN, -1), Some(0));
+        let euclidean = mode(Overflow::Checked, Modulo::Euclidean);
+        assert_eq!(euclidean.apply(Operator::Mod, -7, 3), Some(2));
+        assert_eq!(euclidean.apply(Operator::Mod, -7, -3), Some(2));
+        assert_eq!(euclidean.apply(Operator::Mod, 7, -3), Some(1));
+    }
+
+    #[test]
+    fn test_machine() {
+        let program = Lines::<Instr>::parse("set a 1\nmul a 2\njnz a -1").unwrap();
+        let error = FastMachine::default()
+            .run(&Compiled::new(&program))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Overflow at instruction 1 (mul a 2): 4611686018427387904 * 2 doesn't fit 64 bits"
+        );
+
+        // doubling wraps around to the smallest value and then to 0, which ends the loop
+        let arithmetic = Arithmetic {
+            overflow: Overflow::Wrapping,
+            modulo: Modulo::Euclidean,
+        };
+        let mut machine = FastMachine {
+            arithmetic,
+            ..FastMachine::default()
+        };
+        machine.run(&Compiled::new(&program)).unwrap();
+        assert_eq!(machine.get('a'), 0);
+
+        let program = Compiled::new(&Lines::<Instr>::parse("set a 5\nmod a b").unwrap());
+        let error = FastMachine::default().run(&program).unwrap_err();
+        assert!(matches!(error, Error::DivideByZero { pc: 1, .. }));
+        assert_eq!(
+            error.to_string(),
+            "Division by zero at instruction 1 (mod a b)"
+        );
+
+        let program = Compiled::new(&Lines::<Instr>::parse("set a -7\nmod a 3").unwrap());
+        machine.pc = 0;
+        machine.run(&program).unwrap();
+        assert_eq!(machine.get('a'), 2);
+        let mut machine = FastMachine::default();
+        machine.run(&program).unwrap();
+        assert_eq!(machine.get('a'), -1);
+    }
+}
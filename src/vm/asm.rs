@@ -0,0 +1,327 @@
+//! An assembler for writing programs by hand: jumps go to labels instead of relative offsets,
+//! numbers can have names and comments start with `;` or `#`.
+//!
+//! ```text
+//! const N = 3        ; a name for a number
+//!         set a N
+//! loop:   add a -1
+//!         jgz a loop ; jumps back by one
+//! ```
+//!
+//! A label after the last instruction is allowed, jumping there halts the program.
+use std::collections::HashMap;
+
+use crate::error::{Error, Result};
+use crate::vm::{Arg, Instr};
+
+/// Where a label or constant was defined.
+struct Definition<T> {
+    value: T,
+    line: usize,
+}
+
+/// An instruction with its operands not resolved yet.
+struct Statement<'a> {
+    line: usize,
+    mnemonic: (usize, &'a str),
+    operands: Vec<(usize, &'a str)>,
+}
+
+/// The words of a line without its comment, with their 1-based columns.
+fn tokens(line: &str) -> Vec<(usize, &str)> {
+    let code = line.split(['#', ';']).next().unwrap_or_default();
+    let mut tokens = vec![];
+    let mut start = None;
+    for (idx, c) in code.char_indices().chain([(code.len(), ' ')]) {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some(idx),
+            (true, Some(from)) => {
+                tokens.push((from + 1, &code[from..idx]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+fn is_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn check_name(name: &str, line: usize, column: usize) -> Result<()> {
+    if !is_name(name) {
+        return Err(Error::parse(
+            Some(line),
+            Some(column),
+            format!("{name:?} is not a name, use letters, digits and `_`"),
+        ));
+    }
+    if matches!(name.parse(), Ok(Arg::Reg(_))) {
+        return Err(Error::parse(
+            Some(line),
+            Some(column),
+            format!("{name:?} is a register and can't be a name"),
+        ));
+    }
+    Ok(())
+}
+
+fn define<'a, T>(
+    names: &mut HashMap<&'a str, Definition<T>>,
+    kind: &str,
+    (column, name): (usize, &'a str),
+    definition: Definition<T>,
+) -> Result<()> {
+    if let Some(first) = names.get(name) {
+        return Err(Error::parse(
+            Some(definition.line),
+            Some(column),
+            format!(
+                "duplicate {kind} {name:?}, first defined on line {}",
+                first.line
+            ),
+        ));
+    }
+    names.insert(name, definition);
+    Ok(())
+}
+
+/// Lowers a program to plain instructions with relative jumps, errors point at the line and
+/// column of the problem.
+pub fn assemble(source: &str) -> Result<Vec<Instr>> {
+    let mut labels: HashMap<&str, Definition<usize>> = HashMap::new();
+    let mut constants: HashMap<&str, Definition<isize>> = HashMap::new();
+    let mut statements = vec![];
+    for (idx, text) in source.lines().enumerate() {
+        let line = idx + 1;
+        let mut tokens = tokens(text);
+        if tokens.first().is_some_and(|(_, token)| *token == "const") {
+            let [_, (column, name), (_, "="), (value_column, value)] = tokens[..] else {
+                return Err(Error::parse(
+                    Some(line),
+                    Some(1),
+                    "a constant is defined as `const NAME = VALUE`",
+                ));
+            };
+            check_name(name, line, column)?;
+            let value = value.parse().map_err(|_| {
+                Error::parse(
+                    Some(line),
+                    Some(value_column),
+                    format!("{value:?} is not a number"),
+                )
+            })?;
+            define(
+                &mut constants,
+                "constant",
+                (column, name),
+                Definition { value, line },
+            )?;
+            continue;
+        }
+        while let Some(&(column, token)) = tokens.first() {
+            let Some(name) = token.strip_suffix(':') else {
+                break;
+            };
+            check_name(name, line, column)?;
+            let definition = Definition {
+                value: statements.len(),
+                line,
+            };
+            define(&mut labels, "label", (column, name), definition)?;
+            tokens.remove(0);
+        }
+        if let Some((&mnemonic, operands)) = tokens.split_first() {
+            statements.push(Statement {
+                line,
+                mnemonic,
+                operands: operands.to_vec(),
+            });
+        }
+    }
+    if let Some(name) = labels.keys().find(|name| constants.contains_key(*name)) {
+        let label = &labels[name];
+        return Err(Error::parse(
+            Some(label.line.max(constants[name].line)),
+            None,
+            format!("{name:?} is both a label and a constant"),
+        ));
+    }
+
+    statements
+        .iter()
+        .enumerate()
+        .map(|(idx, statement)| {
+            // only the offset of a jump may be a label, it becomes the distance to it
+            let operand = |(column, token): (usize, &str), offset: bool| -> Result<Arg> {
+                if let Ok(arg) = token.parse() {
+                    return Ok(arg);
+                }
+                let error =
+                    |message: String| Error::parse(Some(statement.line), Some(column), message);
+                match (constants.get(token), labels.get(token)) {
+                    (Some(constant), _) => Ok(Arg::Const(constant.value)),
+                    (_, Some(label)) if offset => {
+                        Ok(Arg::Const(label.value as isize - idx as isize))
+                    }
+                    (_, Some(_)) => Err(error(format!(
+                        "label {token:?} can only be the offset of a jump"
+                    ))),
+                    (None, None) if is_name(token) => {
+                        Err(error(format!("undefined label or constant {token:?}")))
+                    }
+                    (None, None) => Err(error(format!("{token:?} is not a register or a number"))),
+                }
+            };
+            let (column, mnemonic) = statement.mnemonic;
+            let arity = match mnemonic {
+                "snd" | "rcv" => 1,
+                "set" | "add" | "sub" | "mul" | "mod" | "jgz" | "jnz" => 2,
+                _ => {
+                    return Err(Error::parse(
+                        Some(statement.line),
+                        Some(column),
+                        format!("unknown instruction {mnemonic:?}"),
+                    ))
+                }
+            };
+            if statement.operands.len() != arity {
+                return Err(Error::parse(
+                    Some(statement.line),
+                    Some(column),
+                    format!(
+                        "{mnemonic} takes {arity} operands, not {}",
+                        statement.operands.len()
+                    ),
+                ));
+            }
+            let jump = matches!(mnemonic, "jgz" | "jnz");
+            let x = operand(statement.operands[0], false)?;
+            let y = match statement.operands.get(1) {
+                Some(y) => operand(*y, jump)?,
+                None => x,
+            };
+            Ok(match mnemonic {
+                "snd" => Instr::Snd(x),
+                "set" => Instr::Set(x, y),
+                "add" => Instr::Add(x, y),
+                "sub" => Instr::Sub(x, y),
+                "mul" => Instr::Mul(x, y),
+                "mod" => Instr::Mod(x, y),
+                "rcv" => Instr::Rcv(x),
+                "jgz" => Instr::Jgz(x, y),
+                _ => Instr::Jnz(x, y),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::compiled::{Compiled, FastMachine};
+    use crate::vm::Dialect;
+
+    /// Writes a program with a label on every instruction a constant jump lands on.
+    fn with_labels(program: &[Instr]) -> String {
+        let mut lines: Vec<String> = program.iter().map(|instr| format!("  {instr}")).collect();
+        lines.push(String::new());
+        let mut targets = vec![];
+        for (idx, instr) in program.iter().enumerate() {
+            if let Instr::Jgz(x, Arg::Const(offset)) | Instr::Jnz(x, Arg::Const(offset)) = instr {
+                if let Some(target) = idx
+                    .checked_add_signed(*offset)
+                    .filter(|t| *t <= program.len())
+                {
+                    lines[idx] = format!("  {} {x} to_{target} ; was {offset}", instr.name());
+                    targets.push(target);
+                }
+            }
+        }
+        targets.sort();
+        targets.dedup();
+        for target in targets {
+            lines[target] = format!("to_{target}: {}", lines[target].trim());
+        }
+        lines.join("\n")
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for (dialect, input) in [
+            (Dialect::Duet, include_str!("../../inputs/day18.txt")),
+            (Dialect::Coprocessor, include_str!("../../inputs/day23.txt")),
+        ] {
+            let program = dialect.parse_program(input).unwrap();
+            let source = with_labels(&program);
+            assert!(source.contains("to_"));
+            assert_eq!(assemble(&source).unwrap(), program);
+        }
+    }
+
+    #[test]
+    fn test_assemble() {
+        let program = assemble(
+            "\
+# counts down from N
+const N = 3
+        set a N
+loop:   add a -1
+        jgz a loop   ; back by one
+        jgz 1 end
+        set b 1
+end:",
+        )
+        .unwrap();
+        assert_eq!(
+            program,
+            Dialect::Duet
+                .parse_program("set a 3\nadd a -1\njgz a -1\njgz 1 2\nset b 1")
+                .unwrap()
+        );
+        let mut machine = FastMachine::default();
+        machine.run(&Compiled::new(&program)).unwrap();
+        assert_eq!((machine.get('a'), machine.get('b')), (0, 0));
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |source| assemble(source).unwrap_err().to_string();
+        assert_eq!(
+            error("set a 1\njgz a nowhere"),
+            "Parse error at line 2, column 7: undefined label or constant \"nowhere\""
+        );
+        assert_eq!(
+            error("top: set a 1\n  top: jgz a top"),
+            "Parse error at line 2, column 3: duplicate label \"top\", first defined on line 1"
+        );
+        assert_eq!(
+            error("const N = 1\nconst N = 2"),
+            "Parse error at line 2, column 7: duplicate constant \"N\", first defined on line 1"
+        );
+        assert_eq!(
+            error("x: set x 1\nset a x"),
+            "Parse error at line 1, column 1: \"x\" is a register and can't be a name"
+        );
+        assert_eq!(
+            error("top: set a top"),
+            "Parse error at line 1, column 12: label \"top\" can only be the offset of a jump"
+        );
+        assert_eq!(
+            error("add a"),
+            "Parse error at line 1, column 1: add takes 2 operands, not 1"
+        );
+        assert_eq!(
+            error("const N 1"),
+            "Parse error at line 1, column 1: a constant is defined as `const NAME = VALUE`"
+        );
+        assert_eq!(
+            error("N: snd 1\nconst N = 2"),
+            "Parse error at line 2: \"N\" is both a label and a constant"
+        );
+        assert!(error("jmp a 1").contains("unknown instruction \"jmp\""));
+        assert!(error("set a 1.5").contains("\"1.5\" is not a register or a number"));
+    }
+}
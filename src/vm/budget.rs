@@ -0,0 +1,218 @@
+//! Limits on how many steps and how much time a program may take, so that programs that never
+//! halt can be run safely.
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+use crate::format::format_duration;
+use crate::vm::Instr;
+
+/// How often the clock is read, reading it on every step would dominate the run time.
+const CLOCK_INTERVAL: u64 = 1024;
+
+/// `None` doesn't limit anything, so the default budget is unlimited.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Budget {
+    pub steps: Option<u64>,
+    pub time: Option<Duration>,
+}
+
+impl Budget {
+    /// What the solvers give a program, far more than any real input needs. Only steps count,
+    /// so whether an input gets an answer doesn't depend on how fast or busy the machine is.
+    pub const SOLVER: Budget = Budget {
+        steps: Some(1_000_000_000),
+        time: None,
+    };
+
+    pub fn steps(steps: u64) -> Self {
+        Self {
+            steps: Some(steps),
+            time: None,
+        }
+    }
+
+    pub fn time(time: Duration) -> Self {
+        Self {
+            steps: None,
+            time: Some(time),
+        }
+    }
+}
+
+/// The limit that stopped a program.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    Steps(u64),
+    Time(Duration),
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Limit::Steps(steps) => write!(f, "{steps} steps"),
+            Limit::Time(time) => write!(f, "{}", format_duration(*time)),
+        }
+    }
+}
+
+/// Counts the steps of a run against a budget.
+#[derive(Clone, Debug)]
+pub struct Meter {
+    budget: Budget,
+    started: Instant,
+    pub steps: u64,
+}
+
+impl Meter {
+    pub fn new(budget: Budget) -> Self {
+        Self {
+            budget,
+            started: Instant::now(),
+            steps: 0,
+        }
+    }
+
+    /// Whether there's budget left for another step, the limit that ran out if not.
+    #[inline]
+    pub fn check(&self) -> Option<Limit> {
+        if let Some(steps) = self.budget.steps.filter(|steps| self.steps >= *steps) {
+            return Some(Limit::Steps(steps));
+        }
+        match self.budget.time {
+            Some(time)
+                if self.steps.is_multiple_of(CLOCK_INTERVAL) && self.started.elapsed() >= time =>
+            {
+                Some(Limit::Time(time))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn exhausted<S>(&self, limit: Limit, state: S) -> Exhausted<S> {
+        Exhausted {
+            limit,
+            steps: self.steps,
+            elapsed: self.started.elapsed(),
+            state,
+        }
+    }
+}
+
+/// A run that was stopped because it used up its budget, and where it was.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Exhausted<S> {
+    pub limit: Limit,
+    pub steps: u64,
+    pub elapsed: Duration,
+    pub state: S,
+}
+
+impl<S: Display> Display for Exhausted<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.limit {
+            Limit::Steps(_) => write!(
+                f,
+                "ran out of {} in {}",
+                self.limit,
+                format_duration(self.elapsed)
+            )?,
+            Limit::Time(_) => write!(f, "ran out of {} after {} steps", self.limit, self.steps)?,
+        }
+        write!(f, ": {}", self.state)
+    }
+}
+
+/// A single machine, with the registers that aren't zero.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MachineState {
+    pub pc: usize,
+    /// `None` once the program ran off the end.
+    pub instr: Option<Instr>,
+    pub regs: BTreeMap<char, isize>,
+}
+
+impl Display for MachineState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at {}", self.pc)?;
+        if let Some(instr) = &self.instr {
+            write!(f, " ({instr})")?;
+        }
+        for (reg, value) in &self.regs {
+            write!(f, ", {reg}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// How a run with a budget ended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Outcome<T, S = MachineState> {
+    Finished(T),
+    Exhausted(Exhausted<S>),
+}
+
+impl<T, S: Display> Outcome<T, S> {
+    /// The result of a finished run, running out of budget is an error.
+    pub fn into_result(self) -> Result<T> {
+        match self {
+            Outcome::Finished(value) => Ok(value),
+            Outcome::Exhausted(exhausted) => Err(Error::BudgetExhausted(exhausted.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::compiled::{Compiled, FastMachine};
+    use crate::vm::Dialect;
+
+    #[test]
+    fn test_meter() {
+        let mut meter = Meter::new(Budget::steps(3));
+        while meter.check().is_none() {
+            meter.steps += 1;
+        }
+        assert_eq!(meter.steps, 3);
+        assert_eq!(meter.check(), Some(Limit::Steps(3)));
+        assert_eq!(Meter::new(Budget::default()).check(), None);
+        assert_eq!(Budget::SOLVER.time, None);
+        let meter = Meter::new(Budget::time(Duration::ZERO));
+        assert_eq!(meter.check(), Some(Limit::Time(Duration::ZERO)));
+    }
+
+    #[test]
+    fn test_endless_loop() {
+        let program = Dialect::Coprocessor
+            .parse_program("sub a -1\njnz 1 -1")
+            .unwrap();
+        let Outcome::Exhausted(exhausted) = FastMachine::default()
+            .run_with(&Compiled::new(&program), Budget::steps(7))
+            .unwrap()
+        else {
+            panic!("the loop never ends");
+        };
+        let state = MachineState {
+            pc: 1,
+            instr: Some(program[1]),
+            regs: BTreeMap::from([('a', 4)]),
+        };
+        assert_eq!((exhausted.limit, exhausted.steps), (Limit::Steps(7), 7));
+        assert_eq!(exhausted.state, state);
+        assert_eq!(exhausted.state.to_string(), "at 1 (jnz 1 -1), a=4");
+        let error = FastMachine::default()
+            .run_with(
+                &Compiled::new(&program),
+                Budget::time(Duration::from_millis(10)),
+            )
+            .unwrap()
+            .into_result()
+            .unwrap_err();
+        assert!(matches!(error, Error::BudgetExhausted(_)));
+        assert!(error
+            .to_string()
+            .starts_with("Budget exhausted: ran out of 10.0ms after "));
+    }
+}
@@ -0,0 +1,459 @@
+//! Basic blocks, control flow and natural loops of a program.
+use std::collections::BTreeSet;
+use std::fmt::Display;
+
+use crate::vm::{listing, Arg, Instr};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Successor {
+    Block(usize),
+    /// Control leaves the program, which halts it.
+    Exit,
+}
+
+/// Instructions that always run one after another.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Block {
+    pub start: usize,
+    /// One past the last instruction.
+    pub end: usize,
+    /// The jump target first, if the block ends with a jump.
+    pub successors: Vec<Successor>,
+    /// The block ends with a jump by a register, which can land anywhere.
+    pub dynamic: bool,
+}
+
+/// A loop entered through a single block, the header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Loop {
+    pub header: usize,
+    /// The blocks that jump back to the header.
+    pub latches: Vec<usize>,
+    pub blocks: BTreeSet<usize>,
+    /// Index of the innermost loop this one is nested in.
+    pub parent: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cfg {
+    pub blocks: Vec<Block>,
+    pub loops: Vec<Loop>,
+}
+
+/// Whether a jump's condition can be true and false.
+fn outcomes(instr: &Instr) -> (bool, bool) {
+    match instr {
+        Instr::Jgz(Arg::Const(x), _) => (*x > 0, *x <= 0),
+        Instr::Jnz(Arg::Const(x), _) => (*x != 0, *x == 0),
+        _ => (true, true),
+    }
+}
+
+impl Cfg {
+    pub fn new(program: &[Instr]) -> Self {
+        let mut leaders = BTreeSet::from([0]);
+        for (idx, instr) in program.iter().enumerate() {
+            if instr.jump().is_some() {
+                leaders.insert(idx + 1);
+                leaders.extend(instr.target(idx, program.len()));
+            }
+        }
+        let starts: Vec<usize> = leaders
+            .into_iter()
+            .filter(|idx| *idx < program.len())
+            .collect();
+        let block_at = |idx: usize| match starts.binary_search(&idx) {
+            Ok(block) => Successor::Block(block),
+            Err(_) => Successor::Exit,
+        };
+
+        let mut blocks = Vec::new();
+        for (block, start) in starts.iter().enumerate() {
+            let end = starts.get(block + 1).copied().unwrap_or(program.len());
+            let last = &program[end - 1];
+            let mut successors = Vec::new();
+            let mut dynamic = false;
+            let (taken, not_taken) = match last.jump() {
+                Some(_) => outcomes(last),
+                None => (false, true),
+            };
+            if taken {
+                match last.jump() {
+                    Some(Arg::Const(_)) => successors.push(
+                        last.target(end - 1, program.len())
+                            .map_or(Successor::Exit, block_at),
+                    ),
+                    _ => dynamic = true,
+                }
+            }
+            if not_taken && !successors.contains(&block_at(end)) {
+                successors.push(block_at(end));
+            }
+            blocks.push(Block {
+                start: *start,
+                end,
+                successors,
+                dynamic,
+            });
+        }
+
+        let mut cfg = Cfg {
+            blocks,
+            loops: Vec::new(),
+        };
+        cfg.loops = cfg.find_loops();
+        cfg
+    }
+
+    /// The block that contains instruction `idx`.
+    pub fn block_of(&self, idx: usize) -> Option<usize> {
+        self.blocks
+            .iter()
+            .position(|block| (block.start..block.end).contains(&idx))
+    }
+
+    pub fn predecessors(&self, block: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.blocks.len()).filter(move |pred| {
+            self.blocks[*pred]
+                .successors
+                .contains(&Successor::Block(block))
+        })
+    }
+
+    fn block_successors(&self, block: usize) -> impl Iterator<Item = usize> + '_ {
+        self.blocks[block]
+            .successors
+            .iter()
+            .filter_map(|successor| match successor {
+                Successor::Block(block) => Some(*block),
+                Successor::Exit => None,
+            })
+    }
+
+    /// Jumps by a register, whose targets aren't known before running the program.
+    pub fn dynamic_jumps(&self) -> impl Iterator<Item = usize> + '_ {
+        self.blocks
+            .iter()
+            .filter(|block| block.dynamic)
+            .map(|block| block.end - 1)
+    }
+
+    /// The first block and those without predecessors, which only a jump by a register can
+    /// get to.
+    pub fn entries(&self) -> Vec<usize> {
+        (0..self.blocks.len())
+            .filter(|block| *block == 0 || self.predecessors(*block).next().is_none())
+            .collect()
+    }
+
+    /// Blocks that can be reached from the entries.
+    pub fn reachable(&self) -> BTreeSet<usize> {
+        let mut reachable = BTreeSet::new();
+        let mut todo = self.entries();
+        while let Some(block) = todo.pop() {
+            if reachable.insert(block) {
+                todo.extend(self.block_successors(block));
+            }
+        }
+        reachable
+    }
+
+    /// The blocks every path from an entry to each block goes through, empty for the blocks
+    /// that can't be reached.
+    pub fn dominators(&self) -> Vec<BTreeSet<usize>> {
+        let entries = self.entries();
+        let reachable = self.reachable();
+        let mut dominators: Vec<BTreeSet<usize>> = (0..self.blocks.len())
+            .map(|block| {
+                if entries.contains(&block) {
+                    BTreeSet::from([block])
+                } else if reachable.contains(&block) {
+                    reachable.clone()
+                } else {
+                    BTreeSet::new()
+                }
+            })
+            .collect();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &block in reachable.iter().filter(|block| !entries.contains(block)) {
+                let mut new = self
+                    .predecessors(block)
+                    .filter(|pred| reachable.contains(pred))
+                    .map(|pred| dominators[pred].clone())
+                    .reduce(|all, next| &all & &next)
+                    .unwrap_or_default();
+                new.insert(block);
+                if new != dominators[block] {
+                    dominators[block] = new;
+                    changed = true;
+                }
+            }
+        }
+        dominators
+    }
+
+    /// One loop for every block that is jumped back to from a block it dominates.
+    fn find_loops(&self) -> Vec<Loop> {
+        let dominators = self.dominators();
+        let mut loops: Vec<Loop> = Vec::new();
+        for (latch, dominated_by) in dominators.iter().enumerate() {
+            for header in self.block_successors(latch) {
+                if !dominated_by.contains(&header) {
+                    continue;
+                }
+                let idx = match loops.iter().position(|l| l.header == header) {
+                    Some(idx) => idx,
+                    None => {
+                        loops.push(Loop {
+                            header,
+                            latches: Vec::new(),
+                            blocks: BTreeSet::from([header]),
+                            parent: None,
+                        });
+                        loops.len() - 1
+                    }
+                };
+                loops[idx].latches.push(latch);
+                let mut todo = vec![latch];
+                while let Some(block) = todo.pop() {
+                    if loops[idx].blocks.insert(block) {
+                        todo.extend(self.predecessors(block));
+                    }
+                }
+            }
+        }
+        loops.sort_by_key(|l| l.header);
+        for idx in 0..loops.len() {
+            loops[idx].parent = (0..loops.len())
+                .filter(|other| {
+                    *other != idx && loops[*other].blocks.is_superset(&loops[idx].blocks)
+                })
+                .min_by_key(|other| loops[*other].blocks.len());
+        }
+        loops
+    }
+
+    /// How many loops block `block` is in.
+    pub fn depth(&self, block: usize) -> usize {
+        self.loops
+            .iter()
+            .filter(|l| l.blocks.contains(&block))
+            .count()
+    }
+
+    /// Exports the graph for Graphviz, with the instructions of every block. Loops are drawn as
+    /// nested clusters, jumps back to a loop's header in bold.
+    pub fn to_dot(&self, program: &[Instr]) -> String {
+        let listing = listing(program);
+        let lines: Vec<&str> = listing.lines().collect();
+        let mut dot =
+            String::from("digraph program {\n    node [shape=box, fontname=monospace];\n");
+        self.write_clusters(&lines, None, 1, &mut dot);
+        for (idx, block) in self.blocks.iter().enumerate() {
+            for (n, successor) in block.successors.iter().enumerate() {
+                let to = match successor {
+                    Successor::Block(to) => format!("b{to}"),
+                    Successor::Exit => "exit".to_string(),
+                };
+                let mut attrs = Vec::new();
+                let last = &program[block.end - 1];
+                if n == 0 && matches!(last.jump(), Some(Arg::Const(_))) && outcomes(last).0 {
+                    attrs.push("label=\"jump\"".to_string());
+                }
+                if let Successor::Block(to) = successor {
+                    if self
+                        .loops
+                        .iter()
+                        .any(|l| l.header == *to && l.latches.contains(&idx))
+                    {
+                        attrs.push("style=bold".to_string());
+                    }
+                }
+                dot += &format!("    b{idx} -> {to}");
+                if !attrs.is_empty() {
+                    dot += &format!(" [{}]", attrs.join(", "));
+                }
+                dot += ";\n";
+            }
+            if block.dynamic {
+                dot +=
+                    &format!("    b{idx} -> unknown [label=\"jump by register\", style=dashed];\n");
+            }
+        }
+        if self
+            .blocks
+            .iter()
+            .any(|b| b.successors.contains(&Successor::Exit))
+        {
+            dot += "    exit [shape=doublecircle];\n";
+        }
+        if self.blocks.iter().any(|b| b.dynamic) {
+            dot += "    unknown [shape=plaintext, label=\"?\"];\n";
+        }
+        dot += "}\n";
+        dot
+    }
+
+    /// Writes the blocks of loop `parent`, or of no loop, nesting the loops inside it.
+    fn write_clusters(
+        &self,
+        lines: &[&str],
+        parent: Option<usize>,
+        level: usize,
+        dot: &mut String,
+    ) {
+        let indent = "    ".repeat(level);
+        for (idx, block) in self.blocks.iter().enumerate() {
+            let innermost = self
+                .loops
+                .iter()
+                .enumerate()
+                .filter(|(_, l)| l.blocks.contains(&idx))
+                .min_by_key(|(_, l)| l.blocks.len())
+                .map(|(idx, _)| idx);
+            if innermost == parent {
+                let label: String = lines[block.start..block.end]
+                    .iter()
+                    .map(|line| format!("{line}\\l"))
+                    .collect();
+                *dot += &format!("{indent}b{idx} [label=\"{label}\"];\n");
+            }
+        }
+        for (idx, l) in self.loops.iter().enumerate() {
+            if l.parent == parent {
+                *dot += &format!("{indent}subgraph cluster_{idx} {{\n");
+                *dot += &format!(
+                    "{indent}    label=\"loop at {}\";\n",
+                    self.blocks[l.header].start
+                );
+                self.write_clusters(lines, Some(idx), level + 1, dot);
+                *dot += &format!("{indent}}}\n");
+            }
+        }
+    }
+}
+
+impl Display for Cfg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, block) in self.blocks.iter().enumerate() {
+            write!(f, "block {idx}: {}..{}", block.start, block.end)?;
+            let successors: Vec<String> = block
+                .successors
+                .iter()
+                .map(|successor| match successor {
+                    Successor::Block(to) => to.to_string(),
+                    Successor::Exit => "exit".to_string(),
+                })
+                .collect();
+            if !successors.is_empty() {
+                write!(f, " -> {}", successors.join(", "))?;
+            }
+            if block.dynamic {
+                write!(f, ", jump at {} by a register", block.end - 1)?;
+            }
+            writeln!(f)?;
+        }
+        for l in &self.loops {
+            let blocks: Vec<String> = l.blocks.iter().map(|b| b.to_string()).collect();
+            write!(
+                f,
+                "loop at block {}: blocks {}",
+                l.header,
+                blocks.join(", ")
+            )?;
+            if let Some(parent) = l.parent {
+                write!(
+                    f,
+                    ", inside the loop at block {}",
+                    self.loops[parent].header
+                )?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::Dialect;
+
+    #[test]
+    fn test_day23() {
+        let program = Dialect::Coprocessor
+            .parse_program(include_str!("../../inputs/day23.txt"))
+            .unwrap();
+        let cfg = Cfg::new(&program);
+        assert_eq!(cfg.blocks.len(), 14);
+        assert_eq!(
+            cfg.blocks[0].successors,
+            [Successor::Block(2), Successor::Block(1)]
+        );
+        // `jnz 1 5` always jumps
+        assert_eq!(cfg.blocks[1].successors, [Successor::Block(3)]);
+        assert_eq!(cfg.blocks[12].successors, [Successor::Exit]);
+        let loops: Vec<_> = cfg
+            .loops
+            .iter()
+            .map(|l| (l.header, l.latches.clone(), l.blocks.len(), l.parent))
+            .collect();
+        assert_eq!(
+            loops,
+            [
+                (3, vec![13], 10, None),
+                (4, vec![8], 5, Some(0)),
+                (5, vec![7], 3, Some(1)),
+            ]
+        );
+        assert_eq!(cfg.depth(6), 3);
+        assert_eq!(cfg.depth(12), 0);
+        assert_eq!(cfg.dynamic_jumps().count(), 0);
+    }
+
+    #[test]
+    fn test_dynamic_jumps() {
+        let program = Dialect::Duet
+            .parse_program("set a 2\njgz 1 a\nadd b 1\nadd b 2\njgz b -1")
+            .unwrap();
+        let cfg = Cfg::new(&program);
+        assert_eq!(
+            cfg.to_string(),
+            "\
+block 0: 0..2, jump at 1 by a register
+block 1: 2..3 -> 2
+block 2: 3..5 -> 2, exit
+loop at block 2: blocks 2
+"
+        );
+        assert_eq!(cfg.dynamic_jumps().collect::<Vec<_>>(), [1]);
+        assert_eq!(cfg.entries(), [0, 1]);
+        assert!(cfg.to_dot(&program).contains("b0 -> unknown"));
+    }
+
+    #[test]
+    fn test_dot() {
+        let program = Dialect::Coprocessor
+            .parse_program("set a 3\nsub a 1\njnz a -1")
+            .unwrap();
+        assert_eq!(
+            Cfg::new(&program).to_dot(&program),
+            "\
+digraph program {
+    node [shape=box, fontname=monospace];
+    b0 [label=\"0  set a 3\\l\"];
+    subgraph cluster_0 {
+        label=\"loop at 1\";
+        b1 [label=\"1  sub a 1\\l2  jnz a -1  ; -> 1\\l\"];
+    }
+    b0 -> b1;
+    b1 -> b1 [label=\"jump\", style=bold];
+    b1 -> exit;
+    exit [shape=doublecircle];
+}
+"
+        );
+    }
+}
@@ -0,0 +1,432 @@
+//! The interpreter: registers live in an array and instructions are decoded up front.
+use std::collections::BTreeMap;
+
+use crate::error::{Error, Result};
+use crate::vm::arith::{divide_by_zero, overflow, Arithmetic, Operator};
+use crate::vm::budget::{Budget, MachineState, Meter, Outcome};
+use crate::vm::optimize::{CompositeLoop, COMPOSITE_LOOP_LEN};
+use crate::vm::{Arg, Instr, Step};
+
+pub type Registers = [i64; 26];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Src {
+    /// Index of the register, `a` is 0.
+    Reg(u8),
+    Const(i64),
+}
+
+/// An instruction with its registers resolved and its constant jumps turned into targets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Snd(Src),
+    Set(u8, Src),
+    Add(u8, Src),
+    Sub(u8, Src),
+    Mul(u8, Src),
+    Mod(u8, Src),
+    Rcv(Src),
+    /// A jump that's never taken.
+    Nop,
+    /// A jump by a constant that is always taken, to the index it lands on.
+    Goto(usize),
+    GotoIfPositive(Src, usize),
+    GotoIfNonZero(Src, usize),
+    /// Jumps by a register.
+    Jgz(Src, Src),
+    Jnz(Src, Src),
+    /// An instruction that writes to a constant, which fails once it runs.
+    Fault,
+    /// Replaces the first instruction of a loop found by the optimizer.
+    CompositeLoop(CompositeLoop),
+}
+
+fn reg(arg: &Arg) -> Option<u8> {
+    match arg {
+        Arg::Reg(r) => Some(*r as u8 - b'a'),
+        Arg::Const(_) => None,
+    }
+}
+
+fn src(arg: &Arg) -> Src {
+    match arg {
+        Arg::Reg(_) => Src::Reg(reg(arg).expect("a register")),
+        Arg::Const(v) => Src::Const(*v as i64),
+    }
+}
+
+fn decode(idx: usize, instr: &Instr) -> Op {
+    let write = |x: &Arg, op: fn(u8, Src) -> Op, y: &Arg| match reg(x) {
+        Some(x) => op(x, src(y)),
+        None => Op::Fault,
+    };
+    let jump = |x: &Arg,
+                y: &Arg,
+                taken: fn(i64) -> bool,
+                conditional: fn(Src, usize) -> Op,
+                dynamic: fn(Src, Src) -> Op| {
+        // a target past either end of the program halts it
+        let target = |offset: isize| idx.checked_add_signed(offset).unwrap_or(usize::MAX);
+        match (src(x), y) {
+            (Src::Const(x), Arg::Const(y)) if taken(x) => Op::Goto(target(*y)),
+            (Src::Const(_), Arg::Const(_)) => Op::Nop,
+            (x, Arg::Const(y)) => conditional(x, target(*y)),
+            (x, y) => dynamic(x, src(y)),
+        }
+    };
+    match instr {
+        Instr::Snd(x) => Op::Snd(src(x)),
+        Instr::Set(x, y) => write(x, Op::Set, y),
+        Instr::Add(x, y) => write(x, Op::Add, y),
+        Instr::Sub(x, y) => write(x, Op::Sub, y),
+        Instr::Mul(x, y) => write(x, Op::Mul, y),
+        Instr::Mod(x, y) => write(x, Op::Mod, y),
+        Instr::Rcv(x) => Op::Rcv(src(x)),
+        Instr::Jgz(x, y) => jump(x, y, |x| x > 0, Op::GotoIfPositive, Op::Jgz),
+        Instr::Jnz(x, y) => jump(x, y, |x| x != 0, Op::GotoIfNonZero, Op::Jnz),
+    }
+}
+
+/// A program decoded for `FastMachine`, which keeps the source for error messages.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Compiled {
+    pub ops: Vec<Op>,
+    source: Vec<Instr>,
+}
+
+impl Compiled {
+    pub fn new(program: &[Instr]) -> Self {
+        Self {
+            ops: program
+                .iter()
+                .enumerate()
+                .map(|(idx, instr)| decode(idx, instr))
+                .collect(),
+            source: program.to_vec(),
+        }
+    }
+
+    /// Like `new`, with the loops the optimizer knows replaced by native code.
+    pub fn optimized(program: &[Instr]) -> Self {
+        let mut compiled = Self::new(program);
+        for (start, composite) in CompositeLoop::find_all(program) {
+            compiled.ops[start] = Op::CompositeLoop(composite);
+        }
+        compiled
+    }
+
+    /// The instructions the program was decoded from.
+    pub fn source(&self) -> &[Instr] {
+        &self.source
+    }
+}
+
+/// Runs `Compiled` programs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FastMachine {
+    pub pc: usize,
+    pub regs: Registers,
+    pub arithmetic: Arithmetic,
+}
+
+impl FastMachine {
+    pub fn get(&self, reg: char) -> i64 {
+        self.regs[(reg as u8 - b'a') as usize]
+    }
+
+    pub fn set(&mut self, reg: char, value: i64) {
+        self.regs[(reg as u8 - b'a') as usize] = value;
+    }
+
+    pub fn eval_arg(&self, arg: &Arg) -> i64 {
+        self.eval(src(arg))
+    }
+
+    fn eval(&self, src: Src) -> i64 {
+        match src {
+            Src::Reg(r) => self.regs[r as usize],
+            Src::Const(v) => v,
+        }
+    }
+
+    pub fn current<'a>(&self, program: &'a Compiled) -> Option<&'a Op> {
+        program.ops.get(self.pc)
+    }
+
+    #[inline]
+    fn apply(&mut self, program: &Compiled, op: Operator, x: u8, y: Src) -> Result<()> {
+        let (x, y) = (x as usize, self.eval(y));
+        let value = self
+            .arithmetic
+            .apply(op, self.regs[x], y)
+            .ok_or_else(|| overflow(op, self.regs[x], y, self.pc, &program.source[self.pc]))?;
+        self.regs[x] = value;
+        Ok(())
+    }
+
+    #[inline]
+    pub fn step(&mut self, program: &Compiled) -> Result<Step> {
+        let Some(op) = program.ops.get(self.pc) else {
+            return Ok(Step::Halted);
+        };
+        let mut step = Step::Ran;
+        match *op {
+            Op::Snd(x) => step = Step::Sent(self.eval(x) as isize),
+            Op::Set(x, y) => self.regs[x as usize] = self.eval(y),
+            Op::Add(x, y) => self.apply(program, Operator::Add, x, y)?,
+            Op::Sub(x, y) => self.apply(program, Operator::Sub, x, y)?,
+            Op::Mul(x, y) => self.apply(program, Operator::Mul, x, y)?,
+            Op::Mod(x, y) => {
+                if self.eval(y) == 0 {
+                    return Err(divide_by_zero(self.pc, &program.source[self.pc]));
+                }
+                self.apply(program, Operator::Mod, x, y)?;
+            }
+            Op::Rcv(Src::Reg(x)) => return Ok(Step::Waiting(Arg::Reg((b'a' + x) as char))),
+            Op::Rcv(Src::Const(v)) => return Ok(Step::Waiting(Arg::Const(v as isize))),
+            Op::Nop => {}
+            Op::Goto(target) => {
+                self.pc = target;
+                return Ok(step);
+            }
+            Op::GotoIfPositive(x, target) => {
+                if self.eval(x) > 0 {
+                    self.pc = target;
+                    return Ok(step);
+                }
+            }
+            Op::GotoIfNonZero(x, target) => {
+                if self.eval(x) != 0 {
+                    self.pc = target;
+                    return Ok(step);
+                }
+            }
+            Op::Jgz(x, y) => {
+                if self.eval(x) > 0 {
+                    self.jump(self.eval(y));
+                    return Ok(step);
+                }
+            }
+            Op::Jnz(x, y) => {
+                if self.eval(x) != 0 {
+                    self.jump(self.eval(y));
+                    return Ok(step);
+                }
+            }
+            Op::Fault => {
+                let instr = &program.source[self.pc];
+                // dividing by zero is noticed before writing to a constant
+                if let Instr::Mod(_, y) = instr {
+                    if self.eval(src(y)) == 0 {
+                        return Err(divide_by_zero(self.pc, instr));
+                    }
+                }
+                return Err(Error::NotARegister(instr.to_string()));
+            }
+            Op::CompositeLoop(composite) => {
+                if composite.run(&mut self.regs) {
+                    self.pc += COMPOSITE_LOOP_LEN;
+                    return Ok(step);
+                }
+                // the loop wouldn't end, run its `set f 1` and carry on without the shortcut
+                self.regs[composite.f as usize] = 1;
+            }
+        }
+        self.pc += 1;
+        Ok(step)
+    }
+
+    /// Whether the jump the machine is at jumps, `None` if it isn't at a single jump.
+    pub fn jumps(&self, program: &Compiled) -> Option<bool> {
+        match *self.current(program)? {
+            Op::Nop => Some(false),
+            Op::Goto(_) => Some(true),
+            Op::GotoIfPositive(x, _) | Op::Jgz(x, _) => Some(self.eval(x) > 0),
+            Op::GotoIfNonZero(x, _) | Op::Jnz(x, _) => Some(self.eval(x) != 0),
+            _ => None,
+        }
+    }
+
+    fn jump(&mut self, offset: i64) {
+        self.pc = self
+            .pc
+            .checked_add_signed(offset as isize)
+            .unwrap_or(usize::MAX);
+    }
+
+    /// Completes the `rcv` the machine is waiting on by writing `value` into its register.
+    pub fn receive(&mut self, program: &Compiled, value: i64) -> Result<()> {
+        match self.current(program) {
+            Some(Op::Rcv(Src::Reg(x))) => {
+                self.regs[*x as usize] = value;
+                self.pc += 1;
+                Ok(())
+            }
+            Some(Op::Rcv(Src::Const(_))) => {
+                Err(Error::NotARegister(program.source[self.pc].to_string()))
+            }
+            _ => Err(Error::InvalidInput(match program.source.get(self.pc) {
+                Some(instr) => format!("can't receive a value at {instr}"),
+                None => format!("can't receive a value at {}, the program halted", self.pc),
+            })),
+        }
+    }
+
+    /// Moves past the `rcv` the machine is waiting on without receiving anything.
+    pub fn skip(&mut self) {
+        self.pc += 1;
+    }
+
+    /// Runs until the program halts, any `snd` or `rcv` is an error. Returns how many
+    /// instructions ran.
+    pub fn run(&mut self, program: &Compiled) -> Result<usize> {
+        self.run_with(program, Budget::default())?.into_result()
+    }
+
+    /// Like `run`, but stops once `budget` is used up.
+    pub fn run_with(&mut self, program: &Compiled, budget: Budget) -> Result<Outcome<usize>> {
+        let mut meter = Meter::new(budget);
+        while let Some(op) = self.current(program) {
+            if matches!(op, Op::Snd(_) | Op::Rcv(_)) {
+                return Err(Error::InvalidInput(format!(
+                    "{} needs another program to talk to",
+                    program.source[self.pc]
+                )));
+            }
+            if let Some(limit) = meter.check() {
+                return Ok(Outcome::Exhausted(
+                    meter.exhausted(limit, self.state(program)),
+                ));
+            }
+            self.step(program)?;
+            meter.steps += 1;
+        }
+        Ok(Outcome::Finished(meter.steps as usize))
+    }
+
+    pub fn state(&self, program: &Compiled) -> MachineState {
+        MachineState {
+            pc: self.pc,
+            instr: program.source.get(self.pc).copied(),
+            regs: (b'a'..=b'z')
+                .map(char::from)
+                .map(|reg| (reg, self.get(reg) as isize))
+                .filter(|(_, v)| *v != 0)
+                .collect(),
+        }
+    }
+
+    /// The registers `program` mentions and any others that aren't zero, for people to read.
+    pub fn registers(&self, program: &Compiled) -> BTreeMap<char, isize> {
+        let mut regs: BTreeMap<char, isize> = program
+            .source
+            .iter()
+            .flat_map(|instr| instr.args())
+            .filter_map(|arg| match arg {
+                Arg::Reg(r) => Some((r, self.get(r) as isize)),
+                Arg::Const(_) => None,
+            })
+            .collect();
+        regs.extend(
+            (b'a'..=b'z')
+                .map(char::from)
+                .map(|reg| (reg, self.get(reg) as isize))
+                .filter(|(_, v)| *v != 0),
+        );
+        regs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::{generate, Rng};
+    use crate::parse::Lines;
+    use crate::vm::reference::Machine;
+    use crate::vm::Dialect;
+    use aoc_helpers::scaffold::Parse;
+
+    /// Runs both interpreters side by side and checks they agree after every step.
+    fn lockstep(program: &[Instr], a: i64, steps: usize) {
+        let compiled = Compiled::new(program);
+        let mut machine = Machine::default();
+        *machine.get_mut('a') = a as isize;
+        let mut fast = FastMachine::from(&machine);
+        for _ in 0..steps {
+            let (expected, step) = (machine.step(program), fast.step(&compiled));
+            match (&expected, &step) {
+                (Ok(expected), Ok(step)) => assert_eq!(expected, step),
+                (Err(expected), Err(step)) => {
+                    assert_eq!(expected.to_string(), step.to_string());
+                    return;
+                }
+                _ => panic!("{expected:?} but the compiled program gives {step:?}"),
+            }
+            match step.unwrap() {
+                Step::Halted => return,
+                Step::Waiting(_) => {
+                    machine.skip();
+                    fast.skip();
+                }
+                _ => {}
+            }
+            assert_eq!(FastMachine::from(&machine), fast);
+        }
+    }
+
+    #[test]
+    fn test_matches_machine() {
+        for seed in 0..5 {
+            let day23 = generate(23, seed).unwrap();
+            lockstep(
+                &Dialect::Coprocessor.parse_program(&day23).unwrap(),
+                0,
+                20_000,
+            );
+            let day18 = generate(18, seed).unwrap();
+            lockstep(&Dialect::Duet.parse_program(&day18).unwrap(), 0, 20_000);
+        }
+        let mut rng = Rng::new(17);
+        for _ in 0..200 {
+            let program: String = (0..10)
+                .map(|_| {
+                    let arg = |rng: &mut Rng| {
+                        if rng.chance(50) {
+                            rng.choose(&["a", "b"]).to_string()
+                        } else {
+                            rng.range(-3..=3).to_string()
+                        }
+                    };
+                    // no `mul`, which overflows quickly in a loop
+                    let op = rng.choose(&["set", "add", "sub", "mod", "jgz", "jnz"]);
+                    format!("{op} {} {}\n", arg(&mut rng), arg(&mut rng))
+                })
+                .collect();
+            lockstep(&Lines::<Instr>::parse(&program).unwrap(), 3, 1000);
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let program = Dialect::Coprocessor
+            .parse_program("jnz 1 5\njnz 0 -1\njnz a -2\njnz 1 a\nset 1 a")
+            .unwrap();
+        assert_eq!(
+            Compiled::new(&program).ops,
+            [
+                Op::Goto(5),
+                Op::Nop,
+                Op::GotoIfNonZero(Src::Reg(0), 0),
+                Op::Jnz(Src::Const(1), Src::Reg(0)),
+                Op::Fault
+            ]
+        );
+        let error = FastMachine::default()
+            .run(&Compiled::new(&program[4..]))
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Instruction set 1 a needs a register, not a constant"
+        );
+    }
+}
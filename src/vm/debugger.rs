@@ -0,0 +1,529 @@
+//! An interactive debugger for Duet and coprocessor programs, driven by commands on stdin.
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use crate::vm::compiled::FastMachine;
+use crate::vm::duet::{Duet, Status};
+use crate::vm::{listing, Arg};
+
+const HELP: &str = "\
+step [N]            run N instructions of the current program (default: 1)
+continue            run all programs until a breakpoint, a watch or until they are stuck
+break IDX [if CMP]  stop before instruction IDX, optionally only if e.g. `a > 5` or `a == b`
+delete [IDX]        remove the breakpoint at IDX, or all of them
+watch REG           stop whenever REG of any program changes
+unwatch REG         stop watching REG
+program N           switch to program N
+set REG VALUE       change a register of the current program
+regs                print the registers of the current program
+queues              print the values waiting in every program's inbox
+list [IDX]          print the instructions around IDX (default: the current one)
+status              print where every program is
+help                print this
+quit                stop debugging
+An empty line repeats the last command.";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparison {
+    fn holds(self, left: i64, right: i64) -> bool {
+        match self {
+            Comparison::Eq => left == right,
+            Comparison::Ne => left != right,
+            Comparison::Lt => left < right,
+            Comparison::Le => left <= right,
+            Comparison::Gt => left > right,
+            Comparison::Ge => left >= right,
+        }
+    }
+}
+
+/// A comparison between a register and another register or a constant, like `a > 5`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Condition {
+    pub reg: char,
+    pub comparison: Comparison,
+    pub value: Arg,
+}
+
+impl Condition {
+    pub fn holds(&self, machine: &FastMachine) -> bool {
+        self.comparison
+            .holds(machine.get(self.reg), machine.eval_arg(&self.value))
+    }
+}
+
+impl FromStr for Condition {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Error::InvalidInput(format!("{s:?} is not a condition like `a > 5`"));
+        let [reg, comparison, value] = s.split_whitespace().collect::<Vec<_>>()[..] else {
+            return Err(invalid());
+        };
+        let Ok(Arg::Reg(reg)) = reg.parse() else {
+            return Err(invalid());
+        };
+        let comparison = match comparison {
+            "==" => Comparison::Eq,
+            "!=" => Comparison::Ne,
+            "<" => Comparison::Lt,
+            "<=" => Comparison::Le,
+            ">" => Comparison::Gt,
+            ">=" => Comparison::Ge,
+            _ => return Err(invalid()),
+        };
+        Ok(Condition {
+            reg,
+            comparison,
+            value: value.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    Step(usize),
+    Continue,
+    Break(usize, Option<Condition>),
+    Delete(Option<usize>),
+    Watch(char),
+    Unwatch(char),
+    Program(usize),
+    Set(char, isize),
+    Regs,
+    Queues,
+    List(Option<usize>),
+    Status,
+    Help,
+    Quit,
+}
+
+fn parse_reg(s: &str) -> Result<char> {
+    match s.parse() {
+        Ok(Arg::Reg(reg)) => Ok(reg),
+        _ => Err(Error::InvalidInput(format!("{s:?} is not a register"))),
+    }
+}
+
+pub(super) fn parse_number<T: FromStr>(s: &str) -> Result<T> {
+    s.parse()
+        .map_err(|_| Error::InvalidInput(format!("{s:?} is not a number")))
+}
+
+impl FromStr for Command {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, rest) = s.trim().split_once(' ').unwrap_or((s.trim(), ""));
+        let rest = rest.trim();
+        let optional = |rest: &str| -> Result<Option<usize>> {
+            match rest {
+                "" => Ok(None),
+                _ => parse_number(rest).map(Some),
+            }
+        };
+        Ok(match name {
+            "s" | "step" => Command::Step(optional(rest)?.unwrap_or(1)),
+            "c" | "continue" => Command::Continue,
+            "b" | "break" => match rest.split_once(" if ") {
+                Some((idx, condition)) => {
+                    Command::Break(parse_number(idx.trim())?, Some(condition.parse()?))
+                }
+                None => Command::Break(parse_number(rest)?, None),
+            },
+            "d" | "delete" => Command::Delete(optional(rest)?),
+            "w" | "watch" => Command::Watch(parse_reg(rest)?),
+            "unwatch" => Command::Unwatch(parse_reg(rest)?),
+            "p" | "program" => Command::Program(parse_number(rest)?),
+            "set" => {
+                let (reg, value) = rest.split_once(' ').ok_or_else(|| {
+                    Error::InvalidInput("set needs a register and a value".to_string())
+                })?;
+                Command::Set(parse_reg(reg)?, parse_number(value.trim())?)
+            }
+            "r" | "regs" => Command::Regs,
+            "queues" => Command::Queues,
+            "l" | "list" => Command::List(optional(rest)?),
+            "status" => Command::Status,
+            "h" | "help" => Command::Help,
+            "q" | "quit" | "exit" => Command::Quit,
+            _ => {
+                return Err(Error::InvalidInput(format!(
+                    "unknown command {name:?}, try `help`"
+                )))
+            }
+        })
+    }
+}
+
+/// Why running stopped before the programs got stuck.
+enum Stop {
+    /// The program halted or waits for a value.
+    Blocked,
+    Breakpoint,
+    Watch {
+        reg: char,
+        old: i64,
+        new: i64,
+    },
+}
+
+pub struct Debugger {
+    pub duet: Duet,
+    /// The program `step`, `regs` and `list` are about.
+    pub current: usize,
+    pub breakpoints: BTreeMap<usize, Option<Condition>>,
+    pub watches: BTreeSet<char>,
+    /// The program and instruction of the last breakpoint hit, which the next step runs past.
+    stopped_at: Option<(usize, usize)>,
+}
+
+impl Debugger {
+    pub fn new(duet: Duet) -> Self {
+        Self {
+            duet,
+            current: 0,
+            breakpoints: BTreeMap::new(),
+            watches: BTreeSet::new(),
+            stopped_at: None,
+        }
+    }
+
+    /// Stops before a breakpoint, or runs one instruction and stops if it changed a watch.
+    fn step_one(&mut self, idx: usize) -> Result<Option<Stop>> {
+        let machine = &self.duet.programs[idx].machine;
+        let pc = machine.pc;
+        if self.stopped_at.take() != Some((idx, pc)) {
+            let hit = match self.breakpoints.get(&pc) {
+                Some(None) => true,
+                Some(Some(condition)) => condition.holds(machine),
+                None => false,
+            };
+            if hit {
+                self.stopped_at = Some((idx, pc));
+                return Ok(Some(Stop::Breakpoint));
+            }
+        }
+        let before: Vec<i64> = self.watches.iter().map(|reg| machine.get(*reg)).collect();
+        if !self.duet.step_program(idx)? {
+            return Ok(Some(Stop::Blocked));
+        }
+        let machine = &self.duet.programs[idx].machine;
+        for (reg, old) in self.watches.iter().zip(before) {
+            let new = machine.get(*reg);
+            if new != old {
+                return Ok(Some(Stop::Watch {
+                    reg: *reg,
+                    old,
+                    new,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    fn report(&self, stop: &Stop) -> String {
+        let prefix = match stop {
+            Stop::Blocked => String::new(),
+            Stop::Breakpoint => "Breakpoint: ".to_string(),
+            Stop::Watch { reg, old, new } => format!("{reg}: {old} -> {new}, "),
+        };
+        prefix + &self.location()
+    }
+
+    fn location(&self) -> String {
+        let program = &self.duet.programs[self.current];
+        let pc = program.machine.pc;
+        match self.duet.code[self.current].source().get(pc) {
+            None => format!("program {} halted at {pc}\n", self.current),
+            Some(instr) if program.status == Status::Waiting && program.inbox.is_empty() => {
+                format!("program {} waits at {pc}: {instr}\n", self.current)
+            }
+            Some(instr) => format!("program {} at {pc}: {instr}\n", self.current),
+        }
+    }
+
+    /// Runs the programs in turns like `Duet::run`, but stops on breakpoints and watches.
+    fn resume(&mut self) -> Result<String> {
+        let count = self.duet.programs.len();
+        loop {
+            let mut progressed = false;
+            let first = self.current;
+            for idx in (0..count).map(|offset| (first + offset) % count) {
+                while self.duet.programs[idx].can_run() {
+                    match self.step_one(idx)? {
+                        None => progressed = true,
+                        Some(Stop::Blocked) => break,
+                        Some(stop) => {
+                            self.current = idx;
+                            return Ok(self.report(&stop));
+                        }
+                    }
+                }
+            }
+            if !progressed {
+                return Ok(self.duet.diagnose().to_string());
+            }
+        }
+    }
+
+    /// Runs a command and returns what it prints.
+    pub fn execute(&mut self, command: &Command) -> Result<String> {
+        let code = self.duet.code[self.current].source();
+        Ok(match command {
+            Command::Step(count) => {
+                for _ in 0..*count {
+                    if let Some(stop) = self.step_one(self.current)? {
+                        return Ok(self.report(&stop));
+                    }
+                }
+                self.location()
+            }
+            Command::Continue => self.resume()?,
+            Command::Break(idx, condition) => {
+                if *idx >= code.len() {
+                    return Err(Error::InvalidInput(format!(
+                        "there is no instruction {idx}, the program has {}",
+                        code.len()
+                    )));
+                }
+                self.breakpoints.insert(*idx, *condition);
+                String::new()
+            }
+            Command::Delete(Some(idx)) => {
+                if self.breakpoints.remove(idx).is_none() {
+                    return Err(Error::InvalidInput(format!("no breakpoint at {idx}")));
+                }
+                String::new()
+            }
+            Command::Delete(None) => {
+                self.breakpoints.clear();
+                String::new()
+            }
+            Command::Watch(reg) => {
+                self.watches.insert(*reg);
+                String::new()
+            }
+            Command::Unwatch(reg) => {
+                self.watches.remove(reg);
+                String::new()
+            }
+            Command::Program(idx) => {
+                if *idx >= self.duet.programs.len() {
+                    return Err(Error::InvalidInput(format!(
+                        "there is no program {idx}, there are {}",
+                        self.duet.programs.len()
+                    )));
+                }
+                self.current = *idx;
+                self.location()
+            }
+            Command::Set(reg, value) => {
+                self.duet.programs[self.current]
+                    .machine
+                    .set(*reg, *value as i64);
+                String::new()
+            }
+            Command::Regs => {
+                let regs = self.duet.programs[self.current]
+                    .machine
+                    .registers(&self.duet.code[self.current]);
+                let regs: Vec<String> = regs.iter().map(|(r, v)| format!("{r}={v}")).collect();
+                regs.join(", ") + "\n"
+            }
+            Command::Queues => self
+                .duet
+                .programs
+                .iter()
+                .enumerate()
+                .map(|(idx, program)| format!("program {idx}: {:?}\n", program.inbox))
+                .collect(),
+            Command::List(idx) => {
+                let pc = self.duet.programs[self.current].machine.pc;
+                let center = idx.unwrap_or(pc);
+                listing(code)
+                    .lines()
+                    .enumerate()
+                    .skip(center.saturating_sub(3))
+                    .take(7)
+                    .map(|(idx, line)| {
+                        let marker = if idx == pc { "=>" } else { "  " };
+                        let breakpoint = if self.breakpoints.contains_key(&idx) {
+                            '*'
+                        } else {
+                            ' '
+                        };
+                        format!("{marker}{breakpoint} {line}\n")
+                    })
+                    .collect()
+            }
+            Command::Status => self.duet.diagnose().to_string(),
+            Command::Help => format!("{HELP}\n"),
+            Command::Quit => String::new(),
+        })
+    }
+
+    /// Reads commands until `quit` or the end of the input, errors are printed and skipped.
+    pub fn repl(&mut self, input: impl BufRead, mut out: impl Write) -> std::io::Result<()> {
+        write!(out, "{}", self.location())?;
+        read_commands(input, out, "(debug) ", |command| match command {
+            Command::Quit => Ok(None),
+            command => self.execute(command).map(Some),
+        })
+    }
+}
+
+/// Prompts for commands and prints what `execute` returns, until it returns `None` or the input
+/// ends. An empty line repeats the last command that parsed.
+pub(super) fn read_commands<C: FromStr<Err = Error> + Clone>(
+    input: impl BufRead,
+    mut out: impl Write,
+    prompt: &str,
+    mut execute: impl FnMut(&C) -> Result<Option<String>>,
+) -> std::io::Result<()> {
+    let mut last = None;
+    write!(out, "{prompt}")?;
+    out.flush()?;
+    for line in input.lines() {
+        let line = line?;
+        let command = match (line.trim(), &last) {
+            ("", Some(last)) => Ok(C::clone(last)),
+            ("", None) => Err(Error::InvalidInput("type `help` for commands".to_string())),
+            (line, _) => line.parse(),
+        };
+        match command {
+            Ok(command) => {
+                match execute(&command) {
+                    Ok(Some(output)) => write!(out, "{output}")?,
+                    Ok(None) => return Ok(()),
+                    Err(e) => writeln!(out, "{e}")?,
+                }
+                last = Some(command);
+            }
+            Err(e) => writeln!(out, "{e}")?,
+        }
+        write!(out, "{prompt}")?;
+        out.flush()?;
+    }
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::duet::Topology;
+    use crate::vm::{Dialect, Instr};
+
+    fn run(debugger: &mut Debugger, commands: &str) -> String {
+        commands
+            .lines()
+            .map(|command| debugger.execute(&command.parse().unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(
+            "b 3 if a >= b".parse::<Command>().unwrap(),
+            Command::Break(
+                3,
+                Some(Condition {
+                    reg: 'a',
+                    comparison: Comparison::Ge,
+                    value: Arg::Reg('b')
+                })
+            )
+        );
+        assert_eq!("step".parse::<Command>().unwrap(), Command::Step(1));
+        assert_eq!(
+            "set a -4".parse::<Command>().unwrap(),
+            Command::Set('a', -4)
+        );
+        assert!("break 3 if a ~ 1".parse::<Command>().is_err());
+        assert!("watch 1".parse::<Command>().is_err());
+        assert!("jump".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_breakpoints_and_watches() {
+        let code: Vec<Instr> = Dialect::Coprocessor
+            .parse_program("set a 5\nsub a 1\nset b a\njnz a -2")
+            .unwrap();
+        let duet = Duet::copies(&code, 1, &Topology::Pairwise).unwrap();
+        let mut debugger = Debugger::new(duet);
+        assert_eq!(
+            run(&mut debugger, "break 3 if b < 3\ncontinue\nregs"),
+            "Breakpoint: program 0 at 3: jnz a -2\na=2, b=2\n"
+        );
+        assert_eq!(
+            run(&mut debugger, "delete 3\nwatch b\nc\nstep 2\nbreak 3"),
+            "b: 2 -> 1, program 0 at 3: jnz a -2\n\
+             program 0 at 2: set b a\n"
+        );
+        assert_eq!(
+            run(&mut debugger, "list"),
+            "    0  set a 5\n    1  sub a 1\n=>  2  set b a\n  * 3  jnz a -2  ; -> 1\n"
+        );
+        assert!(
+            run(&mut debugger, "delete\nunwatch b\nc").starts_with("Every program ran off the end")
+        );
+        assert!(debugger.execute(&Command::Break(4, None)).is_err());
+    }
+
+    #[test]
+    fn test_breakpoint_before_first_step() {
+        let code = Dialect::Coprocessor
+            .parse_program("set a 2\nsub a 1\njnz a -1")
+            .unwrap();
+        let duet = Duet::copies(&code, 1, &Topology::Pairwise).unwrap();
+        let mut debugger = Debugger::new(duet);
+        assert_eq!(
+            run(&mut debugger, "break 0\nbreak 1\ncontinue\ncontinue\nregs"),
+            "Breakpoint: program 0 at 0: set a 2\n\
+             Breakpoint: program 0 at 1: sub a 1\n\
+             a=2\n"
+        );
+        assert_eq!(
+            run(&mut debugger, "step 3\nregs"),
+            "Breakpoint: program 0 at 1: sub a 1\na=1\n"
+        );
+    }
+
+    #[test]
+    fn test_queues() {
+        let code = Dialect::Duet.parse_program("snd p\nsnd 7\nrcv a").unwrap();
+        let duet = Duet::copies(&code, 2, &Topology::Pairwise).unwrap();
+        let mut debugger = Debugger::new(duet);
+        assert_eq!(
+            run(&mut debugger, "s 2\nqueues\nprogram 1\ns 3"),
+            "program 0 at 2: rcv a\n\
+             program 0: []\nprogram 1: [0, 7]\n\
+             program 1 at 0: snd p\n\
+             program 1 halted at 3\n"
+        );
+        let mut out = Vec::new();
+        debugger
+            .repl("p 0\n\nbogus\ns\nquit\ns\n".as_bytes(), &mut out)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "program 1 halted at 3\n\
+             (debug) program 0 at 2: rcv a\n\
+             (debug) program 0 at 2: rcv a\n\
+             (debug) Invalid input: unknown command \"bogus\", try `help`\n\
+             (debug) program 0 halted at 3\n\
+             (debug) "
+        );
+    }
+}
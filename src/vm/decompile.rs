@@ -0,0 +1,301 @@
+//! Lifts programs into pseudo-code with `if` and `while` blocks, to see what an input computes
+//! without following its jumps by hand.
+//!
+//! The last backward jump to an instruction closes a `do { } while` loop around everything in
+//! between, a forward jump over some instructions is an `if`, with an `else` if the instructions
+//! it skips over end with a jump over the ones after them. Jumps that don't fit become `break`,
+//! `halt` or `goto`.
+use std::collections::BTreeSet;
+
+use crate::vm::{Arg, Instr};
+
+/// `Some(true)` for jumps that are always taken, `Some(false)` for ones that never are.
+fn constant(instr: &Instr) -> Option<bool> {
+    match instr {
+        Instr::Jgz(Arg::Const(x), _) => Some(*x > 0),
+        Instr::Jnz(Arg::Const(x), _) => Some(*x != 0),
+        _ => None,
+    }
+}
+
+/// The condition under which a jump is taken, or isn't with `negate`.
+fn condition(instr: &Instr, negate: bool) -> String {
+    match (instr, negate) {
+        (Instr::Jgz(x, _), false) => format!("{x} > 0"),
+        (Instr::Jgz(x, _), true) => format!("{x} <= 0"),
+        (Instr::Jnz(x, _), false) => format!("{x} != 0"),
+        (Instr::Jnz(x, _), true) => format!("{x} == 0"),
+        _ => unreachable!("only jumps have conditions"),
+    }
+}
+
+fn statement(instr: &Instr) -> String {
+    match instr {
+        Instr::Snd(x) => format!("send({x})"),
+        Instr::Set(x, y) => format!("{x} = {y}"),
+        Instr::Add(x, Arg::Const(y)) | Instr::Sub(x, Arg::Const(y)) if *y < 0 => {
+            let op = if matches!(instr, Instr::Add(..)) {
+                "-"
+            } else {
+                "+"
+            };
+            format!("{x} {op}= {}", -y)
+        }
+        Instr::Add(x, y) => format!("{x} += {y}"),
+        Instr::Sub(x, y) => format!("{x} -= {y}"),
+        Instr::Mul(x, y) => format!("{x} *= {y}"),
+        Instr::Mod(x, y) => format!("{x} %= {y}"),
+        Instr::Rcv(x) => format!("{x} = receive()"),
+        Instr::Jgz(..) | Instr::Jnz(..) => unreachable!("jumps are turned into blocks"),
+    }
+}
+
+struct Decompiler<'a> {
+    program: &'a [Instr],
+    /// Instructions that still need a label for a `goto`.
+    labels: BTreeSet<usize>,
+    gotos: BTreeSet<usize>,
+    lines: Vec<String>,
+}
+
+impl Decompiler<'_> {
+    fn emit(&mut self, depth: usize, line: String) {
+        self.lines.push(format!("{:1$}{line}", "", 4 * depth));
+    }
+
+    fn label(&mut self, idx: usize, depth: usize) {
+        if self.labels.remove(&idx) {
+            self.emit(depth, format!("L{idx}:"));
+        }
+    }
+
+    /// Where jumping to `target` leaves the block, `exit` is the end of the innermost loop.
+    fn leave(&mut self, target: Option<usize>, exit: Option<usize>) -> String {
+        match target {
+            Some(target) if target < self.program.len() => {
+                if Some(target) == exit {
+                    "break".to_string()
+                } else {
+                    self.gotos.insert(target);
+                    format!("goto L{target}")
+                }
+            }
+            _ => "halt".to_string(),
+        }
+    }
+
+    /// The end of the `else` block if the jump at `idx` to `target` is an `if` with one.
+    fn else_end(&self, idx: usize, target: usize, end: usize) -> Option<usize> {
+        let last = target.checked_sub(1).filter(|last| *last > idx)?;
+        let instr = &self.program[last];
+        if constant(instr) != Some(true) {
+            return None;
+        }
+        instr
+            .target(last, self.program.len() + 1)
+            .filter(|after| *after > target && *after <= end)
+    }
+
+    fn structure(&mut self, start: usize, end: usize, depth: usize, exit: Option<usize>) {
+        let mut idx = start;
+        while idx < end {
+            self.label(idx, depth);
+            let latch = (idx..end).rev().find(|latch| {
+                let instr = &self.program[*latch];
+                instr.target(*latch, self.program.len()) == Some(idx)
+                    && constant(instr) != Some(false)
+            });
+            if let Some(latch) = latch {
+                let instr = self.program[latch];
+                let forever = constant(&instr) == Some(true);
+                self.emit(depth, if forever { "loop {" } else { "do {" }.to_string());
+                self.structure(idx, latch, depth + 1, Some(latch + 1));
+                self.label(latch, depth + 1);
+                match forever {
+                    true => self.emit(depth, "}".to_string()),
+                    false => self.emit(depth, format!("}} while {}", condition(&instr, false))),
+                }
+                idx = latch + 1;
+                continue;
+            }
+
+            let instr = self.program[idx];
+            let Some(offset) = instr.jump() else {
+                self.emit(depth, statement(&instr));
+                idx += 1;
+                continue;
+            };
+            let always = constant(&instr);
+            let target = match offset {
+                Arg::Const(offset) => idx.checked_add_signed(offset),
+                Arg::Reg(_) => None,
+            };
+            if always == Some(false) || target == Some(idx + 1) {
+                idx += 1;
+                continue;
+            }
+            match target.filter(|target| *target > idx && *target <= end) {
+                Some(target) if always.is_none() => {
+                    match self.else_end(idx, target, end) {
+                        Some(after) if target - 1 == idx + 1 => {
+                            self.emit(depth, format!("if {} {{", condition(&instr, false)));
+                            self.structure(target, after, depth + 1, exit);
+                            self.emit(depth, "}".to_string());
+                            idx = after;
+                        }
+                        Some(after) => {
+                            self.emit(depth, format!("if {} {{", condition(&instr, true)));
+                            self.structure(idx + 1, target - 1, depth + 1, exit);
+                            self.label(target - 1, depth + 1);
+                            self.emit(depth, "} else {".to_string());
+                            self.structure(target, after, depth + 1, exit);
+                            self.emit(depth, "}".to_string());
+                            idx = after;
+                        }
+                        None => {
+                            self.emit(depth, format!("if {} {{", condition(&instr, true)));
+                            self.structure(idx + 1, target, depth + 1, exit);
+                            self.emit(depth, "}".to_string());
+                            idx = target;
+                        }
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+            let action = match offset {
+                Arg::Const(_) => self.leave(target, exit),
+                Arg::Reg(y) => format!("goto {idx} + {y}"),
+            };
+            if always == Some(true) {
+                self.emit(depth, action);
+            } else {
+                self.emit(depth, format!("if {} {{", condition(&instr, false)));
+                self.emit(depth + 1, action);
+                self.emit(depth, "}".to_string());
+            }
+            idx += 1;
+        }
+    }
+}
+
+/// The program as pseudo-code, `send` and `receive` stand for `snd` and `rcv`.
+pub fn decompile(program: &[Instr]) -> String {
+    let mut decompiler = Decompiler {
+        program,
+        labels: BTreeSet::new(),
+        gotos: BTreeSet::new(),
+        lines: vec![],
+    };
+    // the first pass finds out which instructions need a label
+    decompiler.structure(0, program.len(), 0, None);
+    decompiler.labels = std::mem::take(&mut decompiler.gotos);
+    decompiler.lines.clear();
+    decompiler.structure(0, program.len(), 0, None);
+    decompiler.lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::asm::assemble;
+    use crate::vm::Dialect;
+
+    #[test]
+    fn test_day23() {
+        let program = Dialect::Coprocessor
+            .parse_program(include_str!("../../inputs/day23.txt"))
+            .unwrap();
+        assert_eq!(
+            decompile(&program),
+            "\
+b = 81
+c = b
+if a != 0 {
+    b *= 100
+    b += 100000
+    c = b
+    c += 17000
+}
+loop {
+    f = 1
+    d = 2
+    do {
+        e = 2
+        do {
+            g = d
+            g *= e
+            g -= b
+            if g == 0 {
+                f = 0
+            }
+            e += 1
+            g = e
+            g -= b
+        } while g != 0
+        d += 1
+        g = d
+        g -= b
+    } while g != 0
+    if f == 0 {
+        h += 1
+    }
+    g = b
+    g -= c
+    if g == 0 {
+        halt
+    }
+    b += 17
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_day18() {
+        let program = Dialect::Duet
+            .parse_program(include_str!("../../inputs/day18.txt"))
+            .unwrap();
+        let decompiled = decompile(&program);
+        assert!(decompiled.contains("if p > 0 {\n    goto 3 + p\n}"));
+        assert!(decompiled.contains("do {\n    a *= 2\n    i -= 1\n} while i > 0"));
+        // the loop at 21 is entered from the middle at 23, which takes a `goto`
+        assert!(decompiled.contains("if a <= 0 {\n    L21:\n    do {\n        b = receive()"));
+        assert!(decompiled.contains("if a > 0 {\n    goto L21\n}"));
+        assert!(decompiled.contains(
+            "        if p <= 0 {
+            send(a)
+            a = b
+        } else {
+            send(b)
+            f = 1
+        }"
+        ));
+    }
+
+    #[test]
+    fn test_break() {
+        let program = assemble(
+            "\
+top:    add a 1
+        jgz b out
+        jgz a top
+out:    rcv c
+        jgz 1 -9",
+        )
+        .unwrap();
+        assert_eq!(
+            decompile(&program),
+            "\
+do {
+    a += 1
+    if b > 0 {
+        break
+    }
+} while a > 0
+c = receive()
+halt
+"
+        );
+    }
+}
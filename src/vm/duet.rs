@@ -0,0 +1,503 @@
+//! Runs any number of Duet programs that pass messages to each other with `snd` and `rcv`.
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Display;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::vm::budget::{Budget, Meter, Outcome};
+use crate::vm::compiled::{Compiled, FastMachine};
+use crate::vm::{Instr, Step};
+
+/// Which programs receive the values a program sends.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Topology {
+    /// Every program sends to the next one, the last one to the first.
+    Ring,
+    /// Programs 0 and 1 send to each other, so do 2 and 3 and so on. Without a partner the values
+    /// sent by the last of an odd number of programs are lost.
+    Pairwise,
+    /// Every program sends to all the others.
+    Broadcast,
+    /// The receivers of every program.
+    Custom(Vec<Vec<usize>>),
+}
+
+impl Topology {
+    pub fn receivers(&self, count: usize) -> Result<Vec<Vec<usize>>> {
+        Ok(match self {
+            Topology::Ring => (0..count).map(|idx| vec![(idx + 1) % count]).collect(),
+            Topology::Pairwise => (0..count)
+                .map(|idx| {
+                    let partner = idx ^ 1;
+                    if partner < count {
+                        vec![partner]
+                    } else {
+                        vec![]
+                    }
+                })
+                .collect(),
+            Topology::Broadcast => (0..count)
+                .map(|idx| (0..count).filter(|other| *other != idx).collect())
+                .collect(),
+            Topology::Custom(receivers) => {
+                if receivers.len() != count {
+                    return Err(Error::InvalidInput(format!(
+                        "the topology is for {} programs, not {count}",
+                        receivers.len()
+                    )));
+                }
+                if let Some(receiver) = receivers.iter().flatten().find(|r| **r >= count) {
+                    return Err(Error::InvalidInput(format!(
+                        "there is no program {receiver} to send to"
+                    )));
+                }
+                receivers.clone()
+            }
+        })
+    }
+}
+
+impl FromStr for Topology {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "ring" => Ok(Topology::Ring),
+            "pairwise" => Ok(Topology::Pairwise),
+            "broadcast" => Ok(Topology::Broadcast),
+            _ => Err(Error::InvalidInput(format!("unknown topology {s:?}"))),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Status {
+    #[default]
+    Ready,
+    /// Waiting on a `rcv` with nothing in its inbox.
+    Waiting,
+    Halted,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Program {
+    pub machine: FastMachine,
+    pub inbox: VecDeque<isize>,
+    /// Executed `snd`s, a broadcast counts once.
+    pub sent: usize,
+    pub received: usize,
+    pub status: Status,
+}
+
+impl Program {
+    pub fn can_run(&self) -> bool {
+        match self.status {
+            Status::Ready => true,
+            Status::Waiting => !self.inbox.is_empty(),
+            Status::Halted => false,
+        }
+    }
+}
+
+/// A register that an instruction gave a new value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Change {
+    pub reg: char,
+    pub old: isize,
+    pub new: isize,
+}
+
+/// An instruction that ran.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Executed {
+    pub program: usize,
+    pub pc: usize,
+    /// Whether a `jgz` or `jnz` jumped, `None` for other instructions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jumped: Option<bool>,
+    /// `None` if no register changed, even if one was written to.
+    #[serde(flatten)]
+    pub change: Option<Change>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sent: Option<isize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub received: Option<isize>,
+}
+
+pub struct Duet {
+    pub(super) code: Vec<Compiled>,
+    pub(super) receivers: Vec<Vec<usize>>,
+    pub programs: Vec<Program>,
+}
+
+impl Duet {
+    /// Every program gets its own code, register `p` starts out as its index.
+    pub fn new(code: Vec<&[Instr]>, topology: &Topology) -> Result<Self> {
+        Self::compiled(code.into_iter().map(Compiled::new).collect(), topology)
+    }
+
+    /// `count` programs running the same code.
+    pub fn copies(code: &[Instr], count: usize, topology: &Topology) -> Result<Self> {
+        Self::compiled(vec![Compiled::new(code); count], topology)
+    }
+
+    fn compiled(code: Vec<Compiled>, topology: &Topology) -> Result<Self> {
+        let receivers = topology.receivers(code.len())?;
+        let programs = (0..code.len())
+            .map(|idx| {
+                let mut program = Program::default();
+                program.machine.set('p', idx as i64);
+                program
+            })
+            .collect();
+        Ok(Self {
+            code,
+            receivers,
+            programs,
+        })
+    }
+
+    /// Runs a single instruction of program `idx`, `false` if it is halted or waits for a value.
+    pub(super) fn step_program(&mut self, idx: usize) -> Result<bool> {
+        self.step_observed(idx, &mut |_| {})
+    }
+
+    fn step_observed(&mut self, idx: usize, observe: &mut impl FnMut(Executed)) -> Result<bool> {
+        let code = &self.code[idx];
+        let program = &mut self.programs[idx];
+        let destination = code
+            .source()
+            .get(program.machine.pc)
+            .and_then(Instr::destination);
+        let old = destination.map(|reg| program.machine.get(reg) as isize);
+        let mut executed = Executed {
+            program: idx,
+            pc: program.machine.pc,
+            jumped: program.machine.jumps(code),
+            change: None,
+            sent: None,
+            received: None,
+        };
+        match program.machine.step(code)? {
+            Step::Ran => {}
+            Step::Sent(v) => {
+                executed.sent = Some(v);
+                program.sent += 1;
+                for receiver in &self.receivers[idx] {
+                    self.programs[*receiver].inbox.push_back(v);
+                }
+            }
+            Step::Waiting(_) => match program.inbox.pop_front() {
+                Some(v) => {
+                    program.machine.receive(code, v as i64)?;
+                    executed.received = Some(v);
+                    program.received += 1;
+                }
+                None => {
+                    program.status = Status::Waiting;
+                    return Ok(false);
+                }
+            },
+            Step::Halted => {
+                program.status = Status::Halted;
+                return Ok(false);
+            }
+        }
+        let program = &mut self.programs[idx];
+        program.status = Status::Ready;
+        if let (Some(reg), Some(old)) = (destination, old) {
+            let new = program.machine.get(reg) as isize;
+            if new != old {
+                executed.change = Some(Change { reg, old, new });
+            }
+        }
+        observe(executed);
+        Ok(true)
+    }
+
+    /// Takes turns running the programs until none of them can get any further.
+    pub fn run(&mut self) -> Result<Diagnosis> {
+        self.run_observed(|_| {})
+    }
+
+    /// Like `run`, telling `observe` about every instruction that ran.
+    pub fn run_observed(&mut self, observe: impl FnMut(Executed)) -> Result<Diagnosis> {
+        self.run_metered(Budget::default(), observe)?.into_result()
+    }
+
+    /// Like `run`, but stops once all the programs together used up `budget`.
+    pub fn run_with(&mut self, budget: Budget) -> Result<Outcome<Diagnosis, Diagnosis>> {
+        self.run_metered(budget, |_| {})
+    }
+
+    fn run_metered(
+        &mut self,
+        budget: Budget,
+        mut observe: impl FnMut(Executed),
+    ) -> Result<Outcome<Diagnosis, Diagnosis>> {
+        let mut meter = Meter::new(budget);
+        loop {
+            let mut progressed = false;
+            for idx in 0..self.programs.len() {
+                if !self.programs[idx].can_run() {
+                    continue;
+                }
+                // runs the program until it halts or waits for a value
+                loop {
+                    if let Some(limit) = meter.check() {
+                        return Ok(Outcome::Exhausted(meter.exhausted(limit, self.diagnose())));
+                    }
+                    if !self.step_observed(idx, &mut observe)? {
+                        break;
+                    }
+                    meter.steps += 1;
+                    progressed = true;
+                }
+            }
+            if !progressed {
+                return Ok(Outcome::Finished(self.diagnose()));
+            }
+        }
+    }
+
+    pub fn diagnose(&self) -> Diagnosis {
+        Diagnosis {
+            programs: self
+                .programs
+                .iter()
+                .zip(&self.code)
+                .map(|(program, code)| Snapshot {
+                    status: program.status,
+                    pc: program.machine.pc,
+                    instr: code.source().get(program.machine.pc).copied(),
+                    regs: program.machine.registers(code),
+                    inbox: program.inbox.iter().copied().collect(),
+                    sent: program.sent,
+                    received: program.received,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A program at the moment the scheduler stopped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    pub status: Status,
+    pub pc: usize,
+    /// `None` once the program ran off the end.
+    pub instr: Option<Instr>,
+    /// The registers the program mentions and any others that aren't zero.
+    pub regs: BTreeMap<char, isize>,
+    /// Values that were sent to the program but never received.
+    pub inbox: Vec<isize>,
+    pub sent: usize,
+    pub received: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Reason {
+    /// Every program ran off the end.
+    Finished,
+    /// All the programs that are left wait on a `rcv`, for values none of them will send.
+    Deadlock { waiting: Vec<usize> },
+    /// Some programs wait on a `rcv` after the others already ran off the end.
+    Starved {
+        waiting: Vec<usize>,
+        halted: Vec<usize>,
+    },
+    /// The scheduler was stopped while some programs could still run.
+    Interrupted,
+}
+
+/// Why the programs stopped and where each of them is.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnosis {
+    pub programs: Vec<Snapshot>,
+}
+
+impl Diagnosis {
+    fn with_status(&self, status: Status) -> Vec<usize> {
+        (0..self.programs.len())
+            .filter(|idx| self.programs[*idx].status == status)
+            .collect()
+    }
+
+    pub fn reason(&self) -> Reason {
+        let waiting = self.with_status(Status::Waiting);
+        let halted = self.with_status(Status::Halted);
+        if waiting.len() + halted.len() < self.programs.len()
+            || waiting
+                .iter()
+                .any(|idx| !self.programs[*idx].inbox.is_empty())
+        {
+            Reason::Interrupted
+        } else if waiting.is_empty() {
+            Reason::Finished
+        } else if halted.is_empty() {
+            Reason::Deadlock { waiting }
+        } else {
+            Reason::Starved { waiting, halted }
+        }
+    }
+
+    /// Programs that stopped with values left in their inbox, and how many.
+    pub fn undelivered(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.programs
+            .iter()
+            .enumerate()
+            .filter(|(_, program)| !program.inbox.is_empty())
+            .map(|(idx, program)| (idx, program.inbox.len()))
+    }
+}
+
+fn write_programs(f: &mut std::fmt::Formatter<'_>, programs: &[usize]) -> std::fmt::Result {
+    let names: Vec<String> = programs.iter().map(|idx| idx.to_string()).collect();
+    write!(
+        f,
+        "{} {}",
+        if names.len() == 1 {
+            "program"
+        } else {
+            "programs"
+        },
+        names.join(", ")
+    )
+}
+
+impl Display for Diagnosis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.reason() {
+            Reason::Finished => writeln!(f, "Every program ran off the end")?,
+            Reason::Deadlock { waiting } => {
+                f.write_str("Deadlock: ")?;
+                write_programs(f, &waiting)?;
+                writeln!(f, " wait on rcv with nothing left to receive")?;
+            }
+            Reason::Starved { waiting, halted } => {
+                f.write_str("Starved: ")?;
+                write_programs(f, &waiting)?;
+                f.write_str(" wait on rcv after ")?;
+                write_programs(f, &halted)?;
+                writeln!(f, " ran off the end")?;
+            }
+            Reason::Interrupted => writeln!(f, "Interrupted while programs could still run")?,
+        }
+        for (idx, count) in self.undelivered() {
+            writeln!(f, "Program {idx} left {count} values unreceived")?;
+        }
+        for (idx, program) in self.programs.iter().enumerate() {
+            write!(f, "Program {idx}: {:?} at {}", program.status, program.pc)?;
+            if let Some(instr) = &program.instr {
+                write!(f, " ({instr})")?;
+            }
+            write!(
+                f,
+                ", sent {}, received {}, {} queued",
+                program.sent,
+                program.received,
+                program.inbox.len()
+            )?;
+            for (reg, value) in &program.regs {
+                write!(f, ", {reg}={value}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse::Lines;
+    use aoc_helpers::scaffold::Parse;
+
+    #[test]
+    fn test_topologies() {
+        assert_eq!(Topology::Ring.receivers(3).unwrap(), [[1], [2], [0]]);
+        assert_eq!(
+            Topology::Pairwise.receivers(3).unwrap(),
+            [vec![1], vec![0], vec![]]
+        );
+        assert_eq!(
+            Topology::Broadcast.receivers(3).unwrap(),
+            [[1, 2], [0, 2], [0, 1]]
+        );
+        assert!(Topology::Custom(vec![vec![1]]).receivers(2).is_err());
+        assert!(Topology::Custom(vec![vec![1], vec![2]])
+            .receivers(2)
+            .is_err());
+    }
+
+    #[test]
+    fn test_diagnosis() {
+        let code = Lines::<Instr>::parse("snd p\nrcv a\nrcv b").unwrap();
+        let mut duet = Duet::copies(&code, 2, &Topology::Pairwise).unwrap();
+        let diagnosis = duet.run().unwrap();
+        assert_eq!(
+            diagnosis.reason(),
+            Reason::Deadlock {
+                waiting: vec![0, 1]
+            }
+        );
+        assert_eq!(
+            diagnosis.to_string(),
+            "\
+Deadlock: programs 0, 1 wait on rcv with nothing left to receive
+Program 0: Waiting at 2 (rcv b), sent 1, received 1, 0 queued, a=1, b=0, p=0
+Program 1: Waiting at 2 (rcv b), sent 1, received 1, 0 queued, a=0, b=0, p=1
+"
+        );
+
+        // program 1 halts right away, so program 0 never gets anything
+        let code = Lines::<Instr>::parse("jgz p 3\nsnd 1\nrcv a").unwrap();
+        let diagnosis = Duet::copies(&code, 2, &Topology::Pairwise)
+            .unwrap()
+            .run()
+            .unwrap();
+        assert_eq!(
+            diagnosis.reason(),
+            Reason::Starved {
+                waiting: vec![0],
+                halted: vec![1]
+            }
+        );
+        assert_eq!(diagnosis.undelivered().collect::<Vec<_>>(), [(1, 1)]);
+        assert_eq!(diagnosis.programs[1].instr, None);
+
+        let code = Lines::<Instr>::parse("snd p").unwrap();
+        let diagnosis = Duet::copies(&code, 3, &Topology::Ring)
+            .unwrap()
+            .run()
+            .unwrap();
+        assert_eq!(diagnosis.reason(), Reason::Finished);
+    }
+
+    #[test]
+    fn test_ring() {
+        // program 0 starts a token that every program increments and passes on 10 times
+        let code = Lines::<Instr>::parse(
+            "set i 10\njgz p 2\nsnd 0\nrcv a\nadd a 1\nsnd a\nadd i -1\njgz i -4",
+        )
+        .unwrap();
+        let mut duet = Duet::copies(&code, 4, &Topology::Ring).unwrap();
+        duet.run().unwrap();
+        let counts: Vec<_> = duet.programs.iter().map(|p| (p.sent, p.received)).collect();
+        assert_eq!(counts, [(11, 10), (10, 10), (10, 10), (10, 10)]);
+        assert_eq!(duet.programs[0].machine.get('a'), 40);
+        assert!(duet.programs.iter().all(|p| p.status == Status::Halted));
+        assert_eq!(duet.programs[1].inbox, [40]);
+    }
+
+    #[test]
+    fn test_broadcast() {
+        let code = Lines::<Instr>::parse("snd p\nrcv a\nrcv b\nadd a b").unwrap();
+        let mut duet = Duet::copies(&code, 3, &Topology::Broadcast).unwrap();
+        duet.run().unwrap();
+        let sums: Vec<_> = duet.programs.iter().map(|p| p.machine.get('a')).collect();
+        assert_eq!(sums, [3, 2, 1]);
+    }
+}
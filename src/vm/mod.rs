@@ -0,0 +1,410 @@
+//! The assembly language of the Duet (day 18) and coprocessor (day 23) programs.
+use std::fmt::Display;
+
+use aoc_helpers::prelude::*;
+use rematch::rematch;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::{Error, Result};
+
+pub mod arith;
+pub mod asm;
+pub mod budget;
+pub mod cfg;
+pub mod compiled;
+pub mod debugger;
+pub mod decompile;
+pub mod duet;
+pub mod optimize;
+pub mod profile;
+#[cfg(test)]
+mod reference;
+pub mod threaded;
+pub mod trace;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[rematch]
+pub enum Arg {
+    #[rematch(r"([a-z])")]
+    Reg(char),
+    #[rematch(r"(-?\d+)")]
+    Const(isize),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[rematch]
+pub enum Instr {
+    #[rematch(r"snd (.*)")]
+    Snd(Arg),
+    #[rematch(r"set (.*) (.*)")]
+    Set(Arg, Arg),
+    #[rematch(r"add (.*) (.*)")]
+    Add(Arg, Arg),
+    #[rematch(r"sub (.*) (.*)")]
+    Sub(Arg, Arg),
+    #[rematch(r"mul (.*) (.*)")]
+    Mul(Arg, Arg),
+    #[rematch(r"mod (.*) (.*)")]
+    Mod(Arg, Arg),
+    #[rematch(r"rcv (.*)")]
+    Rcv(Arg),
+    #[rematch(r"jgz (.*) (.*)")]
+    Jgz(Arg, Arg),
+    #[rematch(r"jnz (.*) (.*)")]
+    Jnz(Arg, Arg),
+}
+
+impl Display for Arg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Arg::Reg(r) => write!(f, "{r}"),
+            Arg::Const(v) => write!(f, "{v}"),
+        }
+    }
+}
+
+impl Instr {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Instr::Snd(_) => "snd",
+            Instr::Set(..) => "set",
+            Instr::Add(..) => "add",
+            Instr::Sub(..) => "sub",
+            Instr::Mul(..) => "mul",
+            Instr::Mod(..) => "mod",
+            Instr::Rcv(_) => "rcv",
+            Instr::Jgz(..) => "jgz",
+            Instr::Jnz(..) => "jnz",
+        }
+    }
+
+    /// The offset of `jgz` and `jnz`.
+    pub fn jump(&self) -> Option<Arg> {
+        match self {
+            Instr::Jgz(_, offset) | Instr::Jnz(_, offset) => Some(*offset),
+            _ => None,
+        }
+    }
+
+    /// The arguments of an instruction, in order.
+    pub fn args(&self) -> impl Iterator<Item = Arg> {
+        let (x, y) = match *self {
+            Instr::Snd(x) | Instr::Rcv(x) => (x, None),
+            Instr::Set(x, y)
+            | Instr::Add(x, y)
+            | Instr::Sub(x, y)
+            | Instr::Mul(x, y)
+            | Instr::Mod(x, y)
+            | Instr::Jgz(x, y)
+            | Instr::Jnz(x, y) => (x, Some(y)),
+        };
+        std::iter::once(x).chain(y)
+    }
+
+    /// The register an instruction writes to.
+    pub fn destination(&self) -> Option<char> {
+        match self {
+            Instr::Set(Arg::Reg(r), _)
+            | Instr::Add(Arg::Reg(r), _)
+            | Instr::Sub(Arg::Reg(r), _)
+            | Instr::Mul(Arg::Reg(r), _)
+            | Instr::Mod(Arg::Reg(r), _)
+            | Instr::Rcv(Arg::Reg(r)) => Some(*r),
+            _ => None,
+        }
+    }
+
+    /// Index of the instruction a jump at `idx` lands on, `None` if the offset is a register or
+    /// the jump leaves the program.
+    pub fn target(&self, idx: usize, len: usize) -> Option<usize> {
+        match self.jump()? {
+            Arg::Const(offset) => idx
+                .checked_add_signed(offset)
+                .filter(|target| *target < len),
+            Arg::Reg(_) => None,
+        }
+    }
+}
+
+impl Display for Instr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Instr::Snd(x) | Instr::Rcv(x) => write!(f, "{} {x}", self.name()),
+            Instr::Set(x, y)
+            | Instr::Add(x, y)
+            | Instr::Sub(x, y)
+            | Instr::Mul(x, y)
+            | Instr::Mod(x, y)
+            | Instr::Jgz(x, y)
+            | Instr::Jnz(x, y) => write!(f, "{} {x} {y}", self.name()),
+        }
+    }
+}
+
+/// Instructions are written as assembly, like in the puzzle input.
+impl Serialize for Instr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Instr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse()
+            .map_err(|_| serde::de::Error::custom(format!("invalid instruction {s:?}")))
+    }
+}
+
+/// Prints a program with the index of every instruction and where its jumps land.
+pub fn listing(program: &[Instr]) -> String {
+    let digits = program.len().saturating_sub(1).to_string().len();
+    let width = program
+        .iter()
+        .map(|instr| instr.to_string().len())
+        .max()
+        .unwrap_or_default();
+    let mut listing = String::new();
+    for (idx, instr) in program.iter().enumerate() {
+        let line = format!("{idx:>digits$}  {instr}");
+        match (instr.jump(), instr.target(idx, program.len())) {
+            (Some(Arg::Const(_)), Some(target)) => {
+                listing += &format!("{line:<0$}  ; -> {target}", digits + 2 + width)
+            }
+            (Some(Arg::Const(_)), None) => {
+                listing += &format!("{line:<0$}  ; -> halt", digits + 2 + width)
+            }
+            _ => listing += &line,
+        }
+        listing.push('\n');
+    }
+    listing
+}
+
+/// The instructions a day's programs may use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dialect {
+    /// Day 18: `snd`, `set`, `add`, `mul`, `mod`, `rcv` and `jgz`.
+    Duet,
+    /// Day 23: `set`, `sub`, `mul` and `jnz`.
+    Coprocessor,
+}
+
+impl Dialect {
+    pub fn supports(self, instr: &Instr) -> bool {
+        match self {
+            Dialect::Duet => !matches!(instr, Instr::Sub(..) | Instr::Jnz(..)),
+            Dialect::Coprocessor => matches!(
+                instr,
+                Instr::Set(..) | Instr::Sub(..) | Instr::Mul(..) | Instr::Jnz(..)
+            ),
+        }
+    }
+
+    fn unsupported(self, instr: &Instr) -> String {
+        format!("{} is not part of the {self:?} dialect", instr.name())
+    }
+
+    /// Parses a program, one instruction per line, and checks it only uses the dialect.
+    ///
+    /// Errors point at the line of the source, blank lines included.
+    pub fn parse_program(self, raw_input: &str) -> Result<Vec<Instr>> {
+        raw_input
+            .trim_end()
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(idx, line)| match Instr::parse(line) {
+                Ok(instr) if self.supports(&instr) => Ok(instr),
+                Ok(instr) => Err(Error::parse(Some(idx + 1), None, self.unsupported(&instr))),
+                Err(e) => Err(Error::from(e).at_line(idx + 1)),
+            })
+            .collect()
+    }
+
+    /// Fails on the first instruction that isn't part of the dialect, for programs without a
+    /// source to point at.
+    pub fn check(self, program: &[Instr]) -> Result<()> {
+        match program.iter().position(|instr| !self.supports(instr)) {
+            Some(idx) => Err(Error::InvalidInput(format!(
+                "instruction {idx}: {}",
+                self.unsupported(&program[idx])
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A day's input: a Duet program, parsed with [`Dialect::parse_program`].
+pub struct DuetProgram;
+
+impl Parse for DuetProgram {
+    type Parsed = Vec<Instr>;
+
+    fn parse(raw_input: &str) -> anyhow::Result<Self::Parsed> {
+        Ok(Dialect::Duet.parse_program(raw_input)?)
+    }
+}
+
+/// A day's input: a coprocessor program, parsed with [`Dialect::parse_program`].
+pub struct CoprocessorProgram;
+
+impl Parse for CoprocessorProgram {
+    type Parsed = Vec<Instr>;
+
+    fn parse(raw_input: &str) -> anyhow::Result<Self::Parsed> {
+        Ok(Dialect::Coprocessor.parse_program(raw_input)?)
+    }
+}
+
+/// What a single step did, besides changing registers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    Ran,
+    Sent(isize),
+    /// Stopped on a `rcv`, which is left to the caller: `receive` a value or `skip` it.
+    Waiting(Arg),
+    /// The program counter is outside of the program.
+    Halted,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::Rng;
+    use crate::parse::Lines;
+    use crate::vm::compiled::{Compiled, FastMachine};
+
+    fn program(s: &str) -> Vec<Instr> {
+        Lines::<Instr>::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_step() {
+        let program = Compiled::new(&program(
+            "set a 3\nsub a 1\nsnd a\njnz a -2\nrcv b\nadd b 1",
+        ));
+        let mut machine = FastMachine::default();
+        let mut sent = Vec::new();
+        loop {
+            match machine.step(&program).unwrap() {
+                Step::Ran => {}
+                Step::Sent(v) => sent.push(v),
+                Step::Waiting(arg) => {
+                    assert_eq!(arg, Arg::Reg('b'));
+                    machine.receive(&program, 10).unwrap();
+                }
+                Step::Halted => break,
+            }
+        }
+        assert_eq!(sent, [2, 1, 0]);
+        assert_eq!((machine.get('a'), machine.get('b')), (0, 11));
+        assert_eq!(machine.pc, 6);
+    }
+
+    #[test]
+    fn test_jump_in_front_halts() {
+        let mut machine = FastMachine::default();
+        machine.run(&Compiled::new(&program("jgz 1 -1"))).unwrap();
+        assert_eq!(
+            machine.step(&Compiled::new(&program("set a 1"))).unwrap(),
+            Step::Halted
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = Rng::new(15);
+        let arg = |rng: &mut Rng| {
+            if rng.chance(50) {
+                Arg::Reg(*rng.choose(&['a', 'b', 'p', 'z']))
+            } else {
+                Arg::Const(rng.range(-1000..=1000) as isize)
+            }
+        };
+        for _ in 0..1000 {
+            let (x, y) = (arg(&mut rng), arg(&mut rng));
+            let instr = *rng.choose(&[
+                Instr::Snd(x),
+                Instr::Set(x, y),
+                Instr::Add(x, y),
+                Instr::Sub(x, y),
+                Instr::Mul(x, y),
+                Instr::Mod(x, y),
+                Instr::Rcv(x),
+                Instr::Jgz(x, y),
+                Instr::Jnz(x, y),
+            ]);
+            assert_eq!(instr.to_string().parse::<Instr>().unwrap(), instr);
+        }
+        for raw in [
+            include_str!("../../inputs/day18.txt"),
+            include_str!("../../inputs/day23.txt"),
+        ] {
+            let printed: String = program(raw).iter().map(|i| format!("{i}\n")).collect();
+            assert_eq!(printed, raw);
+        }
+    }
+
+    #[test]
+    fn test_listing() {
+        assert_eq!(
+            listing(&program(
+                "set a -2\njnz a 2\njgz 1 -2\njgz a a\nadd a 1\njnz 1 -10"
+            )),
+            "\
+0  set a -2
+1  jnz a 2    ; -> 3
+2  jgz 1 -2   ; -> 0
+3  jgz a a
+4  add a 1
+5  jnz 1 -10  ; -> halt
+"
+        );
+    }
+
+    #[test]
+    fn test_dialects() {
+        let duet = program("snd 1\nrcv a\njgz a 2\nmod a 2");
+        Dialect::Duet.check(&duet).unwrap();
+        assert_eq!(
+            Dialect::Coprocessor.check(&duet).unwrap_err().to_string(),
+            "Invalid input: instruction 0: snd is not part of the Coprocessor dialect"
+        );
+        assert_eq!(
+            Dialect::Duet
+                .check(&program("set a 1\njnz a 2"))
+                .unwrap_err()
+                .to_string(),
+            "Invalid input: instruction 1: jnz is not part of the Duet dialect"
+        );
+        assert!(FastMachine::default()
+            .run(&Compiled::new(&program("snd 1")))
+            .is_err());
+        assert_eq!(
+            Dialect::Coprocessor
+                .parse_program("set a 1\n\njnz a x\n")
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            Dialect::Coprocessor
+                .parse_program("set a 1\nadd a 2")
+                .unwrap_err()
+                .to_string(),
+            "Parse error at line 2: add is not part of the Coprocessor dialect"
+        );
+        assert_eq!(
+            Dialect::Coprocessor
+                .parse_program("set a 1\n\nadd a 2")
+                .unwrap_err()
+                .to_string(),
+            "Parse error at line 3: add is not part of the Coprocessor dialect"
+        );
+        assert!(matches!(
+            Dialect::Duet.parse_program("\nset a 1\nset 1"),
+            Err(Error::Parse { line: Some(3), .. })
+        ));
+    }
+}
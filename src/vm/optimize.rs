@@ -0,0 +1,259 @@
+//! Finds loops that do something simple the slow way and replaces them with native code.
+//!
+//! The coprocessor program of day 23 checks whether `b` is composite by trying every product
+//! `d * e` with both factors below `b`:
+//!
+//! ```text
+//! set f 1
+//! set d 2
+//! set e 2      ; outer loop over d
+//! set g d      ; inner loop over e
+//! mul g e
+//! sub g b
+//! jnz g 2
+//! set f 0      ; d * e == b
+//! sub e -1
+//! set g e
+//! sub g b
+//! jnz g -8
+//! sub d -1
+//! set g d
+//! sub g b
+//! jnz g -13
+//! ```
+//!
+//! The registers and the first factors may differ.
+use std::collections::HashMap;
+
+use crate::number_theory::is_prime;
+use crate::vm::compiled::Registers;
+use crate::vm::{Arg, Instr};
+
+/// The composite loop of day 23. Upper case letters stand for distinct registers and `#` for
+/// any number, increments match `add x 1` as well.
+const COMPOSITE_LOOP: [&str; 16] = [
+    "set F 1",
+    "set D #",
+    "set E #",
+    "set G D",
+    "mul G E",
+    "sub G B",
+    "jnz G 2",
+    "set F 0",
+    "sub E -1",
+    "set G E",
+    "sub G B",
+    "jnz G -8",
+    "sub D -1",
+    "set G D",
+    "sub G B",
+    "jnz G -13",
+];
+
+/// Instructions of a composite loop, the super-instruction skips all of them.
+pub const COMPOSITE_LOOP_LEN: usize = COMPOSITE_LOOP.len();
+
+/// Sets `f` to 0 if `b` is the product of a `d` in `d0..b` and an `e` in `e0..b`, to 1 if it
+/// isn't. Registers are indices into `Registers`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CompositeLoop {
+    pub f: u8,
+    pub d: u8,
+    pub e: u8,
+    /// Scratch register for the product and the comparisons.
+    pub g: u8,
+    pub b: u8,
+    pub d0: i64,
+    pub e0: i64,
+}
+
+/// What the placeholders of a template stand for.
+#[derive(Default)]
+struct Bindings {
+    regs: HashMap<char, char>,
+    numbers: Vec<isize>,
+}
+
+impl Bindings {
+    fn reg(&self, placeholder: char) -> u8 {
+        self.regs[&placeholder] as u8 - b'a'
+    }
+}
+
+/// Matches `code` against `template`, `None` if it doesn't fit.
+fn bind(template: &[&str], code: &[Instr]) -> Option<Bindings> {
+    let mut bindings = Bindings::default();
+    for (pattern, instr) in template.iter().zip(code) {
+        let instr = match instr {
+            Instr::Add(x, Arg::Const(v)) => Instr::Sub(*x, Arg::Const(-v)),
+            instr => *instr,
+        };
+        let text = instr.to_string();
+        let (mut pattern, mut text) = (pattern.split(' '), text.split(' '));
+        if pattern.next() != text.next() {
+            return None;
+        }
+        for (expected, actual) in pattern.zip(text) {
+            match (expected, actual.parse::<Arg>().ok()?) {
+                ("#", Arg::Const(v)) => bindings.numbers.push(v),
+                (placeholder, Arg::Reg(reg)) if placeholder.len() == 1 => {
+                    let placeholder = placeholder.chars().next()?;
+                    match bindings.regs.get(&placeholder) {
+                        Some(bound) if *bound != reg => return None,
+                        Some(_) => {}
+                        None if bindings.regs.values().any(|bound| *bound == reg) => return None,
+                        None => {
+                            bindings.regs.insert(placeholder, reg);
+                        }
+                    }
+                }
+                (expected, actual) if expected == actual.to_string() => {}
+                _ => return None,
+            }
+        }
+    }
+    Some(bindings)
+}
+
+impl CompositeLoop {
+    /// The loop starting at `start`, if there is one.
+    pub fn find(program: &[Instr], start: usize) -> Option<Self> {
+        let bindings = bind(
+            &COMPOSITE_LOOP,
+            program.get(start..start + COMPOSITE_LOOP_LEN)?,
+        )?;
+        let [d0, e0] = bindings.numbers[..] else {
+            return None;
+        };
+        // with factors below 1 there are products other than divisors to worry about
+        (d0 >= 1 && e0 >= 1).then(|| Self {
+            f: bindings.reg('F'),
+            d: bindings.reg('D'),
+            e: bindings.reg('E'),
+            g: bindings.reg('G'),
+            b: bindings.reg('B'),
+            d0: d0 as i64,
+            e0: e0 as i64,
+        })
+    }
+
+    /// Every loop in the program, with the index it starts at.
+    pub fn find_all(program: &[Instr]) -> Vec<(usize, Self)> {
+        (0..program.len())
+            .filter_map(|start| Some((start, Self::find(program, start)?)))
+            .collect()
+    }
+
+    /// Whether `b` has a factor `d` in `d0..b` that leaves an `e` in `e0..b`.
+    pub fn composite(&self, b: i64) -> bool {
+        // any factor will do when both may start at 2
+        if self.d0 <= 2 && self.e0 <= 2 {
+            return b >= 4 && !is_prime(b as u64);
+        }
+        let fits = |d: i64, e: i64| (self.d0..b).contains(&d) && (self.e0..b).contains(&e);
+        (1..)
+            .take_while(|d: &i64| d * d <= b)
+            .filter(|d| b % d == 0)
+            .any(|d| fits(d, b / d) || fits(b / d, d))
+    }
+
+    /// Leaves the registers like the loop would, `false` if it wouldn't end because `b` is too
+    /// small for either counter to ever reach it, or if its products wouldn't fit a register
+    /// and it's up to the arithmetic what happens.
+    pub fn run(&self, regs: &mut Registers) -> bool {
+        let b = regs[self.b as usize];
+        if b <= self.d0 || b <= self.e0 || (b - 1).checked_mul(b - 1).is_none() {
+            return false;
+        }
+        regs[self.f as usize] = if self.composite(b) { 0 } else { 1 };
+        regs[self.d as usize] = b;
+        regs[self.e as usize] = b;
+        regs[self.g as usize] = 0;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate::generate;
+    use crate::vm::compiled::{Compiled, FastMachine};
+    use crate::vm::Dialect;
+
+    const LOOP: &str = include_str!("../../inputs/day23.txt");
+
+    #[test]
+    fn test_find() {
+        let program = Dialect::Coprocessor.parse_program(LOOP).unwrap();
+        let found = CompositeLoop::find_all(&program);
+        assert_eq!(found.len(), 1);
+        let (start, composite) = found[0];
+        assert_eq!(start, 8);
+        assert_eq!((composite.f, composite.b, composite.g), (5, 1, 6));
+        // the same loop with other registers, factors and increments
+        let renamed = program[8..24]
+            .iter()
+            .map(|instr| instr.to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+            .replace('d', "x")
+            .replace("set e 2", "set e 3")
+            .replace("sub e -1", "add e 1");
+        let renamed = crate::vm::asm::assemble(&renamed).unwrap();
+        let composite = CompositeLoop::find(&renamed, 0).unwrap();
+        assert_eq!((composite.d, composite.e0), (b'x' - b'a', 3));
+        // `g` can't double as `f`
+        let clash = LOOP.replace("set f", "set g");
+        let program = Dialect::Coprocessor.parse_program(&clash).unwrap();
+        assert!(CompositeLoop::find_all(&program).is_empty());
+    }
+
+    /// Runs the loop on its own with `b` set, both as written and with the shortcut.
+    fn both_ways(program: &[Instr], d0: i64, e0: i64, b: i64) {
+        let mut machine = FastMachine::default();
+        machine.set('b', b);
+        let mut fast = machine.clone();
+        machine.run(&Compiled::new(program)).unwrap();
+        let composite = CompositeLoop::find(program, 0).unwrap();
+        assert_eq!((composite.d0, composite.e0), (d0, e0));
+        assert!(composite.run(&mut fast.regs));
+        fast.pc = COMPOSITE_LOOP_LEN;
+        assert_eq!(fast, machine, "b = {b}");
+    }
+
+    #[test]
+    fn test_matches_loop() {
+        let program = Dialect::Coprocessor.parse_program(LOOP).unwrap();
+        for b in 3..60 {
+            both_ways(&program[8..24], 2, 2, b);
+        }
+        let mut shifted = program[8..24].to_vec();
+        shifted[1] = Instr::Set(Arg::Reg('d'), Arg::Const(3));
+        shifted[2] = Instr::Set(Arg::Reg('e'), Arg::Const(1));
+        for b in 4..60 {
+            both_ways(&shifted, 3, 1, b);
+        }
+        let mut regs = [0; 26];
+        regs[1] = 2;
+        assert!(!CompositeLoop::find(&program[8..24], 0)
+            .unwrap()
+            .run(&mut regs));
+        regs[1] = 1 << 32;
+        assert!(!CompositeLoop::find(&program[8..24], 0)
+            .unwrap()
+            .run(&mut regs));
+    }
+
+    #[test]
+    fn test_generated() {
+        for seed in 0..5 {
+            let program = Dialect::Coprocessor
+                .parse_program(&generate(23, seed).unwrap())
+                .unwrap();
+            let (mut plain, mut optimized) = (FastMachine::default(), FastMachine::default());
+            plain.run(&Compiled::new(&program)).unwrap();
+            optimized.run(&Compiled::optimized(&program)).unwrap();
+            assert_eq!(plain, optimized);
+        }
+    }
+}
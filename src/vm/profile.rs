@@ -0,0 +1,141 @@
+//! Counts how often every instruction runs and how often jumps are taken.
+use crate::error::Result;
+use crate::vm::duet::{Diagnosis, Duet, Executed};
+use crate::vm::{listing, Instr};
+
+/// What ran in a single program.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Profile {
+    /// How many times each instruction ran.
+    pub counts: Vec<u64>,
+    /// How many times each jump jumped, zero for other instructions.
+    pub taken: Vec<u64>,
+    pub steps: u64,
+}
+
+impl Profile {
+    pub fn new(len: usize) -> Self {
+        Self {
+            counts: vec![0; len],
+            taken: vec![0; len],
+            steps: 0,
+        }
+    }
+
+    pub fn record(&mut self, executed: &Executed) {
+        self.counts[executed.pc] += 1;
+        if executed.jumped == Some(true) {
+            self.taken[executed.pc] += 1;
+        }
+        self.steps += 1;
+    }
+
+    /// Jumps taken and not taken over the whole program.
+    pub fn jumps(&self, program: &[Instr]) -> (u64, u64) {
+        program
+            .iter()
+            .enumerate()
+            .filter(|(_, instr)| instr.jump().is_some())
+            .fold((0, 0), |(taken, not_taken), (idx, _)| {
+                (
+                    taken + self.taken[idx],
+                    not_taken + self.counts[idx] - self.taken[idx],
+                )
+            })
+    }
+
+    /// Share of all steps spent on instruction `idx`, in percent.
+    pub fn percent(&self, idx: usize) -> f64 {
+        100.0 * self.counts[idx] as f64 / self.steps.max(1) as f64
+    }
+
+    /// The listing of `program` with how often every instruction ran, its share of the steps and
+    /// for jumps how often they jumped.
+    pub fn annotate(&self, program: &[Instr]) -> String {
+        let (taken, not_taken) = self.jumps(program);
+        let mut annotated = format!(
+            "{} steps, {taken} jumps taken, {not_taken} not taken\n",
+            self.steps
+        );
+        let listing = listing(program);
+        let width = listing.lines().map(str::len).max().unwrap_or_default();
+        let digits = self.counts.iter().max().unwrap_or(&0).to_string().len();
+        for (idx, line) in listing.lines().enumerate() {
+            let count = self.counts[idx];
+            let mut row = format!("{count:>digits$} {:>6.2}%  {line}", self.percent(idx));
+            if program[idx].jump().is_some() && count > 0 {
+                row = format!(
+                    "{row:<0$}  taken {1}, not {2}",
+                    digits + 10 + width,
+                    self.taken[idx],
+                    count - self.taken[idx]
+                );
+            }
+            annotated += &row;
+            annotated.push('\n');
+        }
+        annotated
+    }
+}
+
+/// Runs the programs like `Duet::run` and profiles each of them.
+pub fn profile(duet: &mut Duet) -> Result<(Diagnosis, Vec<Profile>)> {
+    let mut profiles: Vec<Profile> = duet
+        .code
+        .iter()
+        .map(|code| Profile::new(code.source().len()))
+        .collect();
+    let diagnosis = duet.run_observed(|executed| profiles[executed.program].record(&executed))?;
+    Ok((diagnosis, profiles))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::duet::Topology;
+    use crate::vm::Dialect;
+
+    #[test]
+    fn test_day23() {
+        let program = Dialect::Coprocessor
+            .parse_program(include_str!("../../inputs/day23.txt"))
+            .unwrap();
+        let mut duet = Duet::copies(&program, 1, &Topology::Pairwise).unwrap();
+        let (_, profiles) = profile(&mut duet).unwrap();
+        let profile = &profiles[0];
+        let muls: u64 = program
+            .iter()
+            .zip(&profile.counts)
+            .filter(|(instr, _)| matches!(instr, Instr::Mul(..)))
+            .map(|(_, count)| count)
+            .sum();
+        // the answer to part 1
+        assert_eq!(muls, 6241);
+        assert_eq!(profile.steps, 50338);
+        assert_eq!(profile.counts[12], 6241);
+        assert_eq!((profile.taken[19], profile.counts[19]), (6162, 6241));
+    }
+
+    #[test]
+    fn test_annotate() {
+        let program = Dialect::Duet
+            .parse_program("set a 3\nadd a -1\njgz a -1\nsnd a\nrcv b")
+            .unwrap();
+        let mut duet = Duet::copies(&program, 2, &Topology::Pairwise).unwrap();
+        let (diagnosis, profiles) = profile(&mut duet).unwrap();
+        // waiting on a `rcv` doesn't count as a step, only receiving does
+        assert_eq!(diagnosis.programs[0].received, 1);
+        assert_eq!(profiles[0], profiles[1]);
+        assert_eq!(
+            profiles[0].annotate(&program),
+            "\
+9 steps, 2 jumps taken, 1 not taken
+1  11.11%  0  set a 3
+3  33.33%  1  add a -1
+3  33.33%  2  jgz a -1  ; -> 1  taken 2, not 1
+1  11.11%  3  snd a
+1  11.11%  4  rcv b
+"
+        );
+    }
+}
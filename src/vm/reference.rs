@@ -0,0 +1,109 @@
+//! The first interpreter, with registers in a `HashMap`. Nothing runs on it anymore, it is kept
+//! as the plain reading of the puzzle that the tests check `FastMachine` against.
+use std::collections::HashMap;
+
+use crate::error::{Error, Result};
+use crate::vm::arith::{divide_by_zero, Arithmetic, Operator};
+use crate::vm::compiled::FastMachine;
+use crate::vm::{Arg, Instr, Step};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Machine {
+    /// Index of the next instruction.
+    pub pc: usize,
+    pub regs: HashMap<char, isize>,
+    pub arithmetic: Arithmetic,
+}
+
+impl Machine {
+    pub fn get(&self, reg: char) -> isize {
+        self.regs.get(&reg).copied().unwrap_or_default()
+    }
+
+    pub fn get_mut(&mut self, reg: char) -> &mut isize {
+        self.regs.entry(reg).or_default()
+    }
+
+    fn eval(&self, arg: &Arg) -> isize {
+        match arg {
+            Arg::Reg(r) => self.get(*r),
+            Arg::Const(v) => *v,
+        }
+    }
+
+    fn eval_mut(&mut self, arg: &Arg, instr: &Instr) -> Result<&mut isize> {
+        match arg {
+            Arg::Reg(r) => Ok(self.get_mut(*r)),
+            Arg::Const(_) => Err(Error::NotARegister(instr.to_string())),
+        }
+    }
+
+    fn jump(&mut self, offset: isize) {
+        // jumping in front of the program halts it just like jumping past its end
+        self.pc = self.pc.checked_add_signed(offset).unwrap_or(usize::MAX);
+    }
+
+    /// Writes `x op y` to `x` with the machine's arithmetic.
+    fn apply(&mut self, instr: &Instr, op: Operator, x: &Arg, y: &Arg) -> Result<()> {
+        let y = self.eval(y);
+        if op == Operator::Mod && y == 0 {
+            return Err(divide_by_zero(self.pc, instr));
+        }
+        let (arithmetic, pc) = (self.arithmetic, self.pc);
+        let x = self.eval_mut(x, instr)?;
+        *x = arithmetic.eval(op, *x as i64, y as i64, pc, instr)? as isize;
+        Ok(())
+    }
+
+    pub fn step(&mut self, program: &[Instr]) -> Result<Step> {
+        let Some(instr) = program.get(self.pc) else {
+            return Ok(Step::Halted);
+        };
+        let mut step = Step::Ran;
+        match instr {
+            Instr::Snd(x) => step = Step::Sent(self.eval(x)),
+            Instr::Set(x, y) => {
+                let y = self.eval(y);
+                *self.eval_mut(x, instr)? = y;
+            }
+            Instr::Add(x, y) => self.apply(instr, Operator::Add, x, y)?,
+            Instr::Sub(x, y) => self.apply(instr, Operator::Sub, x, y)?,
+            Instr::Mul(x, y) => self.apply(instr, Operator::Mul, x, y)?,
+            Instr::Mod(x, y) => self.apply(instr, Operator::Mod, x, y)?,
+            Instr::Rcv(x) => return Ok(Step::Waiting(*x)),
+            Instr::Jgz(x, y) => {
+                if self.eval(x) > 0 {
+                    self.jump(self.eval(y));
+                    return Ok(step);
+                }
+            }
+            Instr::Jnz(x, y) => {
+                if self.eval(x) != 0 {
+                    self.jump(self.eval(y));
+                    return Ok(step);
+                }
+            }
+        }
+        self.pc += 1;
+        Ok(step)
+    }
+
+    /// Moves past the `rcv` the machine is waiting on without receiving anything.
+    pub fn skip(&mut self) {
+        self.pc += 1;
+    }
+}
+
+impl From<&Machine> for FastMachine {
+    fn from(machine: &Machine) -> Self {
+        let mut fast = FastMachine {
+            pc: machine.pc,
+            arithmetic: machine.arithmetic,
+            ..Default::default()
+        };
+        for (reg, value) in &machine.regs {
+            fast.set(*reg, *value as i64);
+        }
+        fast
+    }
+}
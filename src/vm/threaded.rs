@@ -0,0 +1,222 @@
+//! Runs every Duet program on its own thread, passing values over channels.
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::time::Duration;
+
+use crate::error::Result;
+use crate::vm::compiled::Compiled;
+use crate::vm::duet::{Diagnosis, Duet, Program, Status};
+use crate::vm::Step;
+
+/// How often waiting programs check whether everybody is stuck.
+const POLL: Duration = Duration::from_millis(1);
+
+/// How many steps a program runs between checks whether another program failed.
+const FAILED_INTERVAL: u64 = 1024;
+
+/// Counters shared by all the threads, everything is done once both are zero.
+struct Shared {
+    /// Programs that are neither waiting on an empty channel nor halted.
+    active: AtomicUsize,
+    /// Values that were sent but not taken out of a channel yet.
+    pending: AtomicUsize,
+    failed: AtomicBool,
+}
+
+impl Shared {
+    fn done(&self) -> bool {
+        self.failed.load(Ordering::SeqCst)
+            || (self.active.load(Ordering::SeqCst) == 0 && self.pending.load(Ordering::SeqCst) == 0)
+    }
+
+    /// Waits for a value, `None` once no program is left that could send one.
+    fn wait(&self, channel: &Receiver<isize>) -> Option<isize> {
+        self.active.fetch_sub(1, Ordering::SeqCst);
+        loop {
+            match channel.recv_timeout(POLL) {
+                Ok(v) => {
+                    // become active before the value stops counting, so that `done` never sees
+                    // both counters at zero while this program is about to run again
+                    self.active.fetch_add(1, Ordering::SeqCst);
+                    self.pending.fetch_sub(1, Ordering::SeqCst);
+                    return Some(v);
+                }
+                Err(RecvTimeoutError::Timeout) if !self.done() => {}
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+fn run_program(
+    code: &Compiled,
+    mut program: Program,
+    channel: Receiver<isize>,
+    receivers: Vec<Sender<isize>>,
+    shared: &Shared,
+) -> Result<Program> {
+    let mut steps: u64 = 0;
+    loop {
+        steps += 1;
+        // a program that never sends or receives wouldn't notice otherwise
+        if steps.is_multiple_of(FAILED_INTERVAL) && shared.failed.load(Ordering::SeqCst) {
+            return Ok(program);
+        }
+        match program.machine.step(code)? {
+            Step::Ran => {}
+            Step::Sent(v) => {
+                program.sent += 1;
+                for receiver in &receivers {
+                    shared.pending.fetch_add(1, Ordering::SeqCst);
+                    // threads only close their channel early when another program failed
+                    if receiver.send(v).is_err() {
+                        return Ok(program);
+                    }
+                }
+            }
+            Step::Waiting(_) => {
+                let value = match channel.try_recv() {
+                    Ok(v) => {
+                        shared.pending.fetch_sub(1, Ordering::SeqCst);
+                        Some(v)
+                    }
+                    Err(_) => shared.wait(&channel),
+                };
+                match value {
+                    Some(v) => {
+                        program.machine.receive(code, v as i64)?;
+                        program.received += 1;
+                    }
+                    None => {
+                        program.status = Status::Waiting;
+                        return Ok(program);
+                    }
+                }
+            }
+            Step::Halted => {
+                program.status = Status::Halted;
+                // keep taking values off the channel so they don't count as pending forever
+                while let Some(v) = shared.wait(&channel) {
+                    program.inbox.push_back(v);
+                }
+                return Ok(program);
+            }
+        }
+    }
+}
+
+impl Duet {
+    /// Like `run`, but with a thread per program. When every program receives from at most one
+    /// other, values arrive in the same order no matter how the threads are scheduled, so the
+    /// programs end up exactly where `run` leaves them. Values from several senders interleave
+    /// depending on timing.
+    pub fn run_threaded(&mut self) -> Result<Diagnosis> {
+        let (senders, channels): (Vec<_>, Vec<_>) =
+            self.programs.iter().map(|_| mpsc::channel()).unzip();
+        let shared = Shared {
+            active: AtomicUsize::new(self.programs.len()),
+            pending: AtomicUsize::new(0),
+            failed: AtomicBool::new(false),
+        };
+        for (program, sender) in self.programs.iter_mut().zip(&senders) {
+            for v in program.inbox.drain(..) {
+                shared.pending.fetch_add(1, Ordering::SeqCst);
+                sender.send(v).expect("the channel was just created");
+            }
+        }
+
+        let results: Vec<Result<Program>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .programs
+                .iter()
+                .zip(channels)
+                .enumerate()
+                .map(|(idx, (program, channel))| {
+                    let code = &self.code[idx];
+                    let program = program.clone();
+                    let receivers = self.receivers[idx]
+                        .iter()
+                        .map(|r| senders[*r].clone())
+                        .collect();
+                    let shared = &shared;
+                    scope.spawn(move || {
+                        let result = run_program(code, program, channel, receivers, shared);
+                        if result.is_err() {
+                            shared.failed.store(true, Ordering::SeqCst);
+                            shared.active.fetch_sub(1, Ordering::SeqCst);
+                        }
+                        result
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("programs don't panic"))
+                .collect()
+        });
+
+        for (idx, result) in results.into_iter().enumerate() {
+            self.programs[idx] = result?;
+        }
+        Ok(self.diagnose())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::duet::Topology;
+    use crate::vm::{Dialect, Instr};
+
+    fn compare(code: &[Instr], count: usize, topology: Topology) {
+        let mut cooperative = Duet::copies(code, count, &topology).unwrap();
+        let expected = cooperative.run().unwrap();
+        let mut threaded = Duet::copies(code, count, &topology).unwrap();
+        assert_eq!(threaded.run_threaded().unwrap(), expected);
+        assert_eq!(threaded.programs, cooperative.programs);
+    }
+
+    #[test]
+    fn test_matches_cooperative() {
+        let day18 = Dialect::Duet
+            .parse_program(include_str!("../../inputs/day18.txt"))
+            .unwrap();
+        compare(&day18, 2, Topology::Pairwise);
+        compare(&day18, 3, Topology::Ring);
+        let code = Dialect::Duet
+            .parse_program("jgz p 3\nsnd 1\nrcv a\nsnd p\nsnd p")
+            .unwrap();
+        compare(&code, 3, Topology::Pairwise);
+        compare(&code, 2, Topology::Broadcast);
+    }
+
+    #[test]
+    fn test_broadcast() {
+        let code = Dialect::Duet
+            .parse_program("snd p\nrcv a\nrcv b\nrcv c\nadd a b\nadd a c")
+            .unwrap();
+        let mut duet = Duet::copies(&code, 4, &Topology::Broadcast).unwrap();
+        duet.run_threaded().unwrap();
+        let sums: Vec<_> = duet.programs.iter().map(|p| p.machine.get('a')).collect();
+        assert_eq!(sums, [6, 5, 4, 3]);
+        assert!(duet.programs.iter().all(|p| p.status == Status::Halted));
+    }
+
+    #[test]
+    fn test_error_stops_everyone() {
+        let code = Dialect::Duet
+            .parse_program("jgz p 2\nmod a 0\nrcv a")
+            .unwrap();
+        let mut duet = Duet::copies(&code, 2, &Topology::Pairwise).unwrap();
+        assert!(duet.run_threaded().is_err());
+    }
+
+    #[test]
+    fn test_error_stops_an_endless_loop() {
+        let code = Dialect::Duet
+            .parse_program("jgz p 2\nmod a 0\njgz 1 0")
+            .unwrap();
+        let mut duet = Duet::copies(&code, 2, &Topology::Pairwise).unwrap();
+        assert!(duet.run_threaded().is_err());
+    }
+}
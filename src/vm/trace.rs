@@ -0,0 +1,510 @@
+//! Records what every instruction did, as JSON lines, and replays a recording forwards and
+//! backwards.
+//!
+//! The first line describes the programs, every other line is a step:
+//! `{"program":0,"pc":3,"reg":"a","old":1,"new":2}`, with `jumped` for jumps and `sent` or
+//! `received` for the values that were passed around.
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Display;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::vm::debugger::{parse_number, read_commands};
+use crate::vm::duet::{Change, Diagnosis, Duet, Executed};
+use crate::vm::{Arg, Instr};
+
+/// A program before its first step.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Start {
+    pub code: Vec<Instr>,
+    pub pc: usize,
+    pub regs: BTreeMap<char, isize>,
+    pub inbox: Vec<isize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trace {
+    pub programs: Vec<Start>,
+    /// Who receives what each program sends.
+    pub receivers: Vec<Vec<usize>>,
+    pub steps: Vec<Executed>,
+}
+
+/// Runs the programs like `Duet::run` and records every step. The trace is returned even if a
+/// program fails, it then ends with the last step that worked.
+pub fn record(duet: &mut Duet) -> (Trace, Result<Diagnosis>) {
+    let programs = duet
+        .code
+        .iter()
+        .zip(&duet.programs)
+        .map(|(code, program)| Start {
+            code: code.source().to_vec(),
+            pc: program.machine.pc,
+            regs: program.machine.registers(code),
+            inbox: program.inbox.iter().copied().collect(),
+        })
+        .collect();
+    let mut steps = vec![];
+    let result = duet.run_observed(|executed| steps.push(executed));
+    let trace = Trace {
+        programs,
+        receivers: duet.receivers.clone(),
+        steps,
+    };
+    (trace, result)
+}
+
+/// The first line of a trace.
+#[derive(Serialize)]
+struct HeaderRef<'a> {
+    programs: &'a [Start],
+    receivers: &'a [Vec<usize>],
+}
+
+#[derive(Deserialize)]
+struct Header {
+    programs: Vec<Start>,
+    receivers: Vec<Vec<usize>>,
+}
+
+/// Reads a line of JSON, errors point at the line `number`.
+fn from_line<T: DeserializeOwned>(line: &str, number: usize) -> Result<T> {
+    serde_json::from_str(line).map_err(|e| match Error::from(e) {
+        Error::Parse {
+            column, message, ..
+        } => Error::parse(Some(number), column, message),
+        other => other.at_line(number),
+    })
+}
+
+impl Trace {
+    /// Reads the JSON lines written by `Display`.
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+        let (idx, first) = lines
+            .next()
+            .ok_or_else(|| Error::parse(None, None, "The trace is empty"))?;
+        let invalid = |message: String| Error::parse(Some(idx + 1), None, message);
+        let Header {
+            programs,
+            receivers,
+        } = from_line(first, idx + 1)?;
+        if receivers.len() != programs.len() {
+            return Err(invalid(
+                "Every program needs a list of receivers".to_owned(),
+            ));
+        }
+        if let Some(receiver) = receivers.iter().flatten().find(|r| **r >= programs.len()) {
+            return Err(invalid(format!(
+                "There is no program {receiver} to receive"
+            )));
+        }
+        if let Some(reg) = programs
+            .iter()
+            .flat_map(|start| start.regs.keys())
+            .find(|reg| !reg.is_ascii_lowercase())
+        {
+            return Err(invalid(format!("Invalid register {reg:?}")));
+        }
+        let steps = lines
+            .map(|(idx, line)| {
+                let step: Executed = from_line(line, idx + 1)?;
+                let invalid = |message: String| Error::parse(Some(idx + 1), None, message);
+                if programs
+                    .get(step.program)
+                    .is_none_or(|start| step.pc >= start.code.len())
+                {
+                    return Err(invalid(format!(
+                        "There is no instruction {} in program {}",
+                        step.pc, step.program
+                    )));
+                }
+                if let Some(Change { reg, .. }) =
+                    step.change.filter(|c| !c.reg.is_ascii_lowercase())
+                {
+                    return Err(invalid(format!("Invalid register {reg:?}")));
+                }
+                Ok(step)
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            programs,
+            receivers,
+            steps,
+        })
+    }
+}
+
+impl Display for Trace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let header = HeaderRef {
+            programs: &self.programs,
+            receivers: &self.receivers,
+        };
+        writeln!(
+            f,
+            "{}",
+            serde_json::to_string(&header).map_err(|_| std::fmt::Error)?
+        )?;
+        for step in &self.steps {
+            writeln!(
+                f,
+                "{}",
+                serde_json::to_string(step).map_err(|_| std::fmt::Error)?
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A program somewhere in the middle of a trace.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct State {
+    pub pc: usize,
+    /// All the registers the program writes to and the ones it started with.
+    pub regs: BTreeMap<char, isize>,
+    pub inbox: VecDeque<isize>,
+    pub sent: usize,
+    pub received: usize,
+}
+
+/// Moves through a trace, `position` steps in.
+pub struct Replay<'a> {
+    pub trace: &'a Trace,
+    pub position: usize,
+    pub programs: Vec<State>,
+}
+
+impl<'a> Replay<'a> {
+    pub fn new(trace: &'a Trace) -> Self {
+        Self {
+            trace,
+            position: 0,
+            programs: trace
+                .programs
+                .iter()
+                .map(|start| {
+                    // every register the program writes to, so that undoing a step restores 0
+                    let mut regs: BTreeMap<char, isize> = start
+                        .code
+                        .iter()
+                        .filter_map(|instr| Some((instr.destination()?, 0)))
+                        .collect();
+                    regs.extend(&start.regs);
+                    State {
+                        pc: start.pc,
+                        regs,
+                        inbox: start.inbox.iter().copied().collect(),
+                        ..Default::default()
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Applies the next step, `false` at the end of the trace.
+    pub fn forward(&mut self) -> bool {
+        let Some(step) = self.trace.steps.get(self.position) else {
+            return false;
+        };
+        let program = &mut self.programs[step.program];
+        program.pc = step.pc + 1;
+        if step.jumped == Some(true) {
+            if let Some(offset) = self.trace.programs[step.program].code[step.pc].jump() {
+                let offset = match offset {
+                    Arg::Reg(r) => program.regs.get(&r).copied().unwrap_or_default(),
+                    Arg::Const(v) => v,
+                };
+                program.pc = step.pc.checked_add_signed(offset).unwrap_or(usize::MAX);
+            }
+        }
+        if let Some(change) = step.change {
+            program.regs.insert(change.reg, change.new);
+        }
+        if step.received.is_some() {
+            program.inbox.pop_front();
+            program.received += 1;
+        }
+        if let Some(v) = step.sent {
+            program.sent += 1;
+            for receiver in &self.trace.receivers[step.program] {
+                self.programs[*receiver].inbox.push_back(v);
+            }
+        }
+        self.position += 1;
+        true
+    }
+
+    /// Undoes the last step, `false` at the start of the trace.
+    pub fn backward(&mut self) -> bool {
+        let Some(position) = self.position.checked_sub(1) else {
+            return false;
+        };
+        let step = &self.trace.steps[position];
+        if step.sent.is_some() {
+            for receiver in &self.trace.receivers[step.program] {
+                self.programs[*receiver].inbox.pop_back();
+            }
+            self.programs[step.program].sent -= 1;
+        }
+        let program = &mut self.programs[step.program];
+        if let Some(v) = step.received {
+            program.inbox.push_front(v);
+            program.received -= 1;
+        }
+        if let Some(change) = step.change {
+            program.regs.insert(change.reg, change.old);
+        }
+        program.pc = step.pc;
+        self.position = position;
+        true
+    }
+
+    /// Moves forwards or backwards until `position` steps are applied, or the trace ends.
+    pub fn goto(&mut self, position: usize) {
+        while self.position < position && self.forward() {}
+        while self.position > position && self.backward() {}
+    }
+
+    /// What the last applied step did.
+    pub fn describe(&self) -> String {
+        let total = self.trace.steps.len();
+        let Some(position) = self.position.checked_sub(1) else {
+            return format!("at the start of {total} steps\n");
+        };
+        let step = &self.trace.steps[position];
+        let instr = self.trace.programs[step.program].code[step.pc];
+        let mut description = format!(
+            "step {} of {total}: program {} at {}: {instr}",
+            self.position, step.program, step.pc
+        );
+        match step.jumped {
+            Some(true) => description += ", jumped",
+            Some(false) => description += ", didn't jump",
+            None => {}
+        }
+        if let Some(Change { reg, old, new }) = step.change {
+            description += &format!(", {reg}: {old} -> {new}");
+        }
+        if let Some(v) = step.sent {
+            description += &format!(", sent {v}");
+        }
+        if let Some(v) = step.received {
+            description += &format!(", received {v}");
+        }
+        description + "\n"
+    }
+
+    /// Registers, program counters and inboxes of all the programs.
+    pub fn state(&self) -> String {
+        self.programs
+            .iter()
+            .enumerate()
+            .map(|(idx, program)| {
+                let regs: Vec<String> = program
+                    .regs
+                    .iter()
+                    .map(|(r, v)| format!("{r}={v}"))
+                    .collect();
+                format!(
+                    "program {idx} at {}: {}, inbox {:?}, sent {}, received {}\n",
+                    program.pc,
+                    regs.join(", "),
+                    program.inbox,
+                    program.sent,
+                    program.received
+                )
+            })
+            .collect()
+    }
+
+    pub fn execute(&mut self, command: &Command) -> Result<String> {
+        let moved = match *command {
+            Command::Next(count) => (0..count).take_while(|_| self.forward()).count(),
+            Command::Back(count) => (0..count).take_while(|_| self.backward()).count(),
+            Command::Goto(position) => {
+                if position > self.trace.steps.len() {
+                    return Err(Error::InvalidInput(format!(
+                        "the trace has only {} steps",
+                        self.trace.steps.len()
+                    )));
+                }
+                self.goto(position);
+                return Ok(self.describe());
+            }
+            Command::State => return Ok(self.state()),
+            Command::Help => return Ok(format!("{HELP}\n")),
+            Command::Quit => return Ok(String::new()),
+        };
+        if moved == 0 {
+            return Err(Error::InvalidInput(
+                if matches!(command, Command::Next(_)) {
+                    "at the end of the trace"
+                } else {
+                    "at the start of the trace"
+                }
+                .to_string(),
+            ));
+        }
+        Ok(self.describe())
+    }
+
+    /// Reads commands until `quit` or the end of the input, errors are printed and skipped.
+    pub fn repl(&mut self, input: impl BufRead, mut out: impl Write) -> std::io::Result<()> {
+        write!(out, "{}", self.describe())?;
+        read_commands(input, out, "(replay) ", |command| match command {
+            Command::Quit => Ok(None),
+            command => self.execute(command).map(Some),
+        })
+    }
+}
+
+const HELP: &str = "\
+next [N]  apply the next N steps (default: 1)
+back [N]  undo the last N steps (default: 1)
+goto N    move to the point after step N, 0 is the start
+state     print the registers and inboxes of every program
+help      print this
+quit      stop replaying";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    Next(usize),
+    Back(usize),
+    Goto(usize),
+    State,
+    Help,
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, rest) = s.trim().split_once(' ').unwrap_or((s.trim(), ""));
+        let count = |rest: &str| match rest.trim() {
+            "" => Ok(1),
+            rest => parse_number(rest),
+        };
+        Ok(match name {
+            "n" | "next" => Command::Next(count(rest)?),
+            "b" | "back" => Command::Back(count(rest)?),
+            "g" | "goto" => Command::Goto(parse_number(rest.trim())?),
+            "s" | "state" => Command::State,
+            "h" | "help" => Command::Help,
+            "q" | "quit" | "exit" => Command::Quit,
+            _ => {
+                return Err(Error::InvalidInput(format!(
+                    "unknown command {name:?}, try `help`"
+                )))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vm::duet::Topology;
+    use crate::vm::Dialect;
+
+    fn trace(code: &str, count: usize) -> Trace {
+        let program = Dialect::Duet.parse_program(code).unwrap();
+        let mut duet = Duet::copies(&program, count, &Topology::Pairwise).unwrap();
+        let (trace, result) = record(&mut duet);
+        result.unwrap();
+        trace
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let trace = trace(include_str!("../../inputs/day18.txt"), 2);
+        let written = trace.to_string();
+        assert_eq!(Trace::parse(&written).unwrap(), trace);
+        assert_eq!(written.lines().count(), trace.steps.len() + 1);
+        let small = self::trace("set a 3\nadd a -1\njgz a -1\nsnd a\nrcv b", 2).to_string();
+        let mut lines = small.lines().skip(1);
+        assert_eq!(
+            lines.next().unwrap(),
+            "{\"program\":0,\"pc\":0,\"reg\":\"a\",\"old\":0,\"new\":3}"
+        );
+        assert_eq!(
+            lines.nth(1).unwrap(),
+            "{\"program\":0,\"pc\":2,\"jumped\":true}"
+        );
+        assert!(small.contains("\"received\":0"));
+        let error = Trace::parse(&small.replace("\"pc\":2,", "\"pc\":9,")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            Error::parse(Some(4), None, "There is no instruction 9 in program 0").to_string()
+        );
+        assert!(Trace::parse("").is_err());
+    }
+
+    #[test]
+    fn test_replay() {
+        let code = include_str!("../../inputs/day18.txt");
+        let program = Dialect::Duet.parse_program(code).unwrap();
+        let mut duet = Duet::copies(&program, 2, &Topology::Pairwise).unwrap();
+        let (trace, result) = record(&mut duet);
+        let diagnosis = result.unwrap();
+        let mut replay = Replay::new(&trace);
+        let start = replay.programs.clone();
+        replay.goto(trace.steps.len());
+        for (state, snapshot) in replay.programs.iter().zip(&diagnosis.programs) {
+            assert_eq!(state.pc, snapshot.pc);
+            for (reg, value) in &snapshot.regs {
+                assert_eq!(state.regs[reg], *value);
+            }
+            assert_eq!(state.inbox, snapshot.inbox);
+            // the answer to part 2
+            assert_eq!(
+                (state.sent, state.received),
+                (snapshot.sent, snapshot.received)
+            );
+        }
+        assert_eq!(replay.programs[1].sent, 7620);
+        let end = replay.programs.clone();
+        replay.goto(1000);
+        let middle = replay.programs.clone();
+        replay.goto(0);
+        assert_eq!(replay.programs, start);
+        replay.goto(1000);
+        assert_eq!(replay.programs, middle);
+        replay.goto(trace.steps.len());
+        assert_eq!(replay.programs, end);
+    }
+
+    #[test]
+    fn test_commands() {
+        let trace = trace("set a 3\nadd a -1\njgz a -1\nsnd a\nrcv b", 2);
+        let mut replay = Replay::new(&trace);
+        let mut out = vec![];
+        replay
+            .repl(
+                "n 2\n\nb\ngoto 100\nback 99\nstate\nq\n".as_bytes(),
+                &mut out,
+            )
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+at the start of 18 steps
+(replay) step 2 of 18: program 0 at 1: add a -1, a: 3 -> 2
+(replay) step 4 of 18: program 0 at 1: add a -1, a: 2 -> 1
+(replay) step 3 of 18: program 0 at 2: jgz a -1, jumped
+(replay) Invalid input: the trace has only 18 steps
+(replay) at the start of 18 steps
+(replay) program 0 at 0: a=0, b=0, inbox [], sent 0, received 0
+program 1 at 0: a=0, b=0, p=1, inbox [], sent 0, received 0
+(replay) "
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use advent_of_code_2017::days::{day18, day21, day24, day25};
+use advent_of_code_2017::vm::duet::{Duet, Reason, Topology};
+use aoc_helpers::prelude::*;
+
+#[test]
+fn test_duet_programs_from_outside_the_crate() {
+    let instrs =
+        VecFromLines::<day18::Instr>::parse("snd 1\nsnd p\nrcv a\nrcv b\nrcv c\n").unwrap();
+    let mut duet = Duet::copies(&instrs, 2, &Topology::Pairwise).unwrap();
+    let diagnosis = duet.run().unwrap();
+    assert_eq!(
+        diagnosis.reason(),
+        Reason::Deadlock {
+            waiting: vec![0, 1]
+        }
+    );
+    let sent: Vec<_> = diagnosis.programs.iter().map(|p| p.sent).collect();
+    assert_eq!(sent, [2, 2]);
+    assert_eq!(diagnosis.programs[0].regs[&'b'], 1);
+}
+
+#[test]
+fn test_rule_set_from_outside_the_crate() {
+    let rules = VecFromLines::<day21::Rule>::parse(concat!(
+        "../.# => ##./#../...\n",
+        ".#./..#/### => #..#/..../..../#..#\n",
+    ))
+    .unwrap();
+    let rules = day21::RuleSet::from(rules.as_slice());
+    let image = rules.iterate(day21::BitMap::starting_pattern()).unwrap();
+    assert_eq!(image.size(), 4);
+}
+
+#[test]
+fn test_bridge_builder_and_machine_from_outside_the_crate() {
+    let components = VecFromLines::<day24::Component>::parse("0/2\n2/2\n2/3\n").unwrap();
+    let mut builder = day24::BridgeBuilder::new(&components).unwrap();
+    let initial_state = builder.initial_state();
+    walk::walk_deep(&mut builder, initial_state);
+    assert_eq!(builder.best_strength, 11);
+
+    let configs =
+        VecFromMultiLines::<day25::Config>::parse(include_str!("../inputs/day25-sample.txt"))
+            .unwrap();
+    let mut machine = day25::Machine::from(configs.as_slice());
+    machine.run().unwrap();
+    assert_eq!(machine.tape.ones(), 3);
+}